@@ -0,0 +1,37 @@
+//! Compares repeated lookups using `get` with a `&str` against `get_path` with a pre-parsed `CfgPath`.
+//!
+//! Run with `cargo bench --bench path`.
+
+use cfgmap::{CfgMap, CfgPath, CfgValue::*};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const DEPTH: usize = 16;
+const LOOKUPS: u32 = 200_000;
+
+fn time(name: &str, mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..LOOKUPS {
+        f();
+    }
+
+    let elapsed = start.elapsed();
+    println!("{:<8} {:>10.1?} total, {:>8.1?} per lookup", name, elapsed, elapsed / LOOKUPS);
+    elapsed
+}
+
+fn main() {
+    let path = (0..DEPTH).map(|i| format!("level{}", i)).collect::<Vec<_>>().join("/");
+
+    let mut cmap = CfgMap::new();
+    cmap.add_auto(&path, Int(1)).unwrap();
+
+    let parsed = CfgPath::new(&path);
+    assert_eq!(cmap.get(&path), cmap.get_path(&parsed));
+
+    let with_str = time("get", || { black_box(cmap.get(black_box(&path))); });
+    let with_path = time("get_path", || { black_box(cmap.get_path(black_box(&parsed))); });
+
+    println!("get_path is {:.2}x as fast as get at a depth of {}", with_str.as_secs_f64() / with_path.as_secs_f64(), DEPTH);
+}
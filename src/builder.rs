@@ -0,0 +1,113 @@
+use super::{CfgError, CfgMap, CfgValue, MergeStrategy};
+use std::env;
+use std::path::PathBuf;
+
+/// A single layer of a `CfgBuilder`.
+#[derive(Debug, Clone)]
+enum Layer {
+    File(PathBuf),
+    Env(String),
+    Overrides(Vec<(String, CfgValue)>),
+}
+
+/// Assembles a `CfgMap` from multiple layers, such as a file, environment variables and command line overrides.
+///
+/// Layers are applied in the order they were added when calling `build`, each one deep-merging over the
+/// previous ones using `MergeStrategy::Replace`. Nothing is read until `build` is called.
+///
+/// - `from_file` loads a file using `CfgMap::load_from_file`.
+/// - `with_env` reads every environment variable starting with `PREFIX_`. The rest of the name is lowercased,
+///   with `__` separating segments, so `APP_HTTP__PORT` sets `http/port`. Values are coerced using `CfgValue::coerce`.
+/// - `with_overrides` sets each path to the given value.
+///
+/// ## Examples
+/// ```
+/// use cfgmap::{CfgBuilder, CfgValue::*};
+///
+/// std::env::set_var("BUILDER_DOC_HTTP__PORT", "8080");
+/// std::env::set_var("BUILDER_DOC_DEBUG", "true");
+///
+/// let cmap = CfgBuilder::new()
+///     .with_env("BUILDER_DOC")
+///     .with_overrides(&[("debug", Bool(false)), ("http/host", Str("localhost".into()))])
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(cmap.get("http/port"), Some(&Int(8080)));
+/// assert_eq!(cmap.get("http/host"), Some(&Str("localhost".into())));
+/// assert_eq!(cmap.get("debug"), Some(&Bool(false)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CfgBuilder {
+    layers: Vec<Layer>,
+}
+
+impl CfgBuilder {
+    /// Creates a new builder without any layers.
+    pub fn new() -> CfgBuilder {
+        CfgBuilder::default()
+    }
+
+    /// Adds a layer loaded from the file at `path`. See `CfgMap::load_from_file` for the supported formats.
+    pub fn from_file<P: Into<PathBuf>>(mut self, path: P) -> CfgBuilder {
+        self.layers.push(Layer::File(path.into()));
+        self
+    }
+
+    /// Adds a layer read from the environment variables starting with `prefix`, followed by a `_`.
+    pub fn with_env(mut self, prefix: &str) -> CfgBuilder {
+        self.layers.push(Layer::Env(prefix.into()));
+        self
+    }
+
+    /// Adds a layer setting each path within `overrides` to its value.
+    pub fn with_overrides(mut self, overrides: &[(&str, CfgValue)]) -> CfgBuilder {
+        let overrides = overrides.iter().map(|(path, value)| (path.to_string(), value.clone())).collect();
+
+        self.layers.push(Layer::Overrides(overrides));
+        self
+    }
+
+    /// Applies every layer in order, returning the resulting map.
+    ///
+    /// Returns an error if a file couldn't be loaded. See `CfgMap::load_from_file`.
+    pub fn build(self) -> Result<CfgMap, CfgError> {
+        let mut map = CfgMap::new();
+
+        for layer in self.layers {
+            let layer = match layer {
+                Layer::File(path) => CfgMap::load_from_file(path)?,
+                Layer::Env(prefix) => env_to_cfg(&prefix),
+                Layer::Overrides(overrides) => CfgMap::from_flat(overrides, '/'),
+            };
+
+            map.merge_with(layer, MergeStrategy::Replace);
+        }
+
+        Ok(map)
+    }
+}
+
+/// Converts the environment variables starting with `prefix` into a map.
+///
+/// Variables are sorted by name, so that the result doesn't depend on the order of the environment.
+/// Variables whose name or value isn't valid unicode, or whose path contains an empty segment, are skipped.
+fn env_to_cfg(prefix: &str) -> CfgMap {
+    let prefix = format!("{}_", prefix);
+
+    let mut vars: Vec<(String, CfgValue)> = env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+        .filter_map(|(name, value)| {
+            let path = name.strip_prefix(&prefix)?.to_lowercase().replace("__", "/");
+
+            if path.split('/').any(str::is_empty) {
+                return None;
+            }
+
+            Some((path, CfgValue::Str(value).coerce()))
+        })
+        .collect();
+
+    vars.sort_by(|(a, _), (b, _)| a.cmp(b));
+    CfgMap::from_flat(vars, '/')
+}
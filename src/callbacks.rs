@@ -0,0 +1,41 @@
+use super::CfgValue;
+use std::fmt;
+
+/// A callback registered using `CfgMap::on_change`.
+pub(crate) type ChangeCallback = Box<dyn Fn(&CfgValue) + Send + Sync>;
+
+/// The callbacks registered on a `CfgMap`, along with the paths they watch.
+///
+/// Callbacks can't be cloned, so they're treated as not being part of the map's contents:
+/// cloning results in no callbacks.
+#[derive(Default)]
+pub(crate) struct Callbacks(Vec<(String, ChangeCallback)>);
+
+impl Callbacks {
+    pub(crate) fn push(&mut self, path: String, callback: ChangeCallback) {
+        self.0.push((path, callback));
+    }
+
+    /// Returns the callbacks whose watched path is affected by a write to `written`.
+    ///
+    /// A write affects a watched path if it's the same path, or if either is within the other.
+    pub(crate) fn affected_by<'a>(&'a self, written: &'a str) -> impl Iterator<Item = (&'a str, &'a ChangeCallback)> {
+        let within = |inner: &str, outer: &str| inner.strip_prefix(outer).map_or(false, |rest| rest.starts_with('/'));
+
+        self.0.iter()
+            .filter(move |(path, _)| path == written || within(path, written) || within(written, path))
+            .map(|(path, callback)| (path.as_str(), callback))
+    }
+}
+
+impl Clone for Callbacks {
+    fn clone(&self) -> Self {
+        Callbacks::default()
+    }
+}
+
+impl fmt::Debug for Callbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} callback(s)", self.0.len())
+    }
+}
@@ -166,13 +166,40 @@
 //!assert!(cmap.get("person/1/name").check_that(IsExactlyStr("b".into())));
 //! ```
 
+// `is_some_and` would raise the minimum supported Rust version to 1.70, so `map_or(false, ...)` is used instead.
+#![allow(clippy::unnecessary_map_or)]
+
 use std::collections::HashMap;
+use std::collections::hash_map;
+use std::iter::FromIterator;
 mod conditions;
-pub use conditions::{Checkable, Condition};
+pub use conditions::{Checkable, Condition, Relation};
+mod error;
+pub use error::CfgError;
+mod frozen;
+pub use frozen::FrozenCfgMap;
+mod path;
+pub use path::CfgPath;
+mod diff;
+pub use diff::CfgDiff;
+mod hash;
+mod callbacks;
+use callbacks::Callbacks;
+mod visitor;
+pub use visitor::CfgVisitor;
+mod shared;
+pub use shared::SharedCfgMap;
+mod builder;
+pub use builder::CfgBuilder;
+use std::cmp::Ordering;
 use std::concat;
+use std::fmt;
 use std::mem;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::net::SocketAddr;
 
 #[macro_use]
 mod macros;
@@ -180,6 +207,9 @@ mod macros;
 #[cfg(feature = "from_json")]
 use serde_json::Value as JsonValue;
 
+#[cfg(feature = "from_json")]
+use std::io::{BufReader, Read, Write};
+
 #[cfg(feature = "from_toml")]
 use toml::value::Value as TomlValue;
 
@@ -195,12 +225,19 @@ mod from_toml;
 #[cfg(feature = "from_yaml")]
 mod from_yaml;
 
+mod from_ini;
+
+mod file;
+
 #[cfg(feature = "from_toml")]
 use toml::value::Datetime;
 
 #[cfg(feature = "generator")]
 use rand::Rng;
 
+/// The default maximum depth of a `CfgMap`. See `CfgMap::max_depth`.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
 // The type contained within `CfgValue::Int`
 pub(crate) type _Int = i64;
 
@@ -274,6 +311,73 @@ pub enum CfgValue {
     Alias(usize),
 }
 
+/// Represents the type of a `CfgValue`, without its contents.
+/// 
+/// This is useful for data-driven validation, for example using `Condition::IsType`,
+/// where the expected type can be stored in a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CfgType {
+    /// The type of `CfgValue::Int`.
+    Int,
+
+    /// The type of `CfgValue::Float`.
+    Float,
+
+    /// The type of `CfgValue::Str`.
+    Str,
+
+    /// The type of `CfgValue::Bool`.
+    Bool,
+
+    /// The type of `CfgValue::Map`.
+    Map,
+
+    /// The type of `CfgValue::List`.
+    List,
+
+    /// The type of `CfgValue::Datetime`. Only available if using `from_toml`.
+    #[cfg(feature = "from_toml")]
+    Datetime,
+
+    /// The type of `CfgValue::Null`. Only available if using `from_json` or `from_yaml`.
+    #[cfg(any(feature = "from_json", feature = "from_yaml"))]
+    Null,
+
+    /// The type of `CfgValue::BadValue`. Only available if using `from_yaml`.
+    #[cfg(feature = "from_yaml")]
+    BadValue,
+
+    /// The type of `CfgValue::Alias`. Only available if using `from_yaml`.
+    #[cfg(feature = "from_yaml")]
+    Alias,
+}
+
+impl CfgType {
+    /// Returns the name of the type, which is the name of its variant in lowercase.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CfgType::Int => "int",
+            CfgType::Float => "float",
+            CfgType::Str => "str",
+            CfgType::Bool => "bool",
+            CfgType::Map => "map",
+            CfgType::List => "list",
+
+            #[cfg(feature = "from_toml")]
+            CfgType::Datetime => "datetime",
+
+            #[cfg(any(feature = "from_json", feature = "from_yaml"))]
+            CfgType::Null => "null",
+
+            #[cfg(feature = "from_yaml")]
+            CfgType::BadValue => "badvalue",
+
+            #[cfg(feature = "from_yaml")]
+            CfgType::Alias => "alias",
+        }
+    }
+}
+
 impl CfgValue {
     /// Assumes the value is a `CfgMap` and attempts to execute `.get()` on it.
     /// Returns `None` if the value isn't a `CfgMap`, or for any reasons `.get()`
@@ -289,6 +393,29 @@ impl CfgValue {
         self.as_map_mut().and_then(|map| map.get_mut(key))
     }
 
+    /// Assumes the value is a `List` and attempts to get the element at `index`.
+    /// Returns `None` if the value isn't a `List`, or `index` is out of bounds.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// 
+    /// let list = List(vec![Int(1), Int(2)]);
+    /// 
+    /// assert_eq!(list.get_index(1), Some(&Int(2)));
+    /// assert_eq!(list.get_index(2), None);
+    /// assert_eq!(Int(1).get_index(0), None);
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<&CfgValue> {
+        self.as_list().and_then(|list| list.get(index))
+    }
+
+    /// Assumes the value is a `List` and attempts to get a mutable reference to the element at `index`.
+    /// Returns `None` if the value isn't a `List`, or `index` is out of bounds.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut CfgValue> {
+        self.as_list_mut().and_then(|list| list.get_mut(index))
+    }
+
     #[cfg(feature = "generator")]
     /// Generates an integer using the value, using `rand`. There are 3 total cases this function handles:
     /// 
@@ -318,11 +445,11 @@ impl CfgValue {
             Some(*self.as_int().unwrap())
         }
         else if self.check_that(validate(1)) {
-            Some(*self.as_list().unwrap().get(0).unwrap().as_int().unwrap())
+            Some(*self.as_list().unwrap().first().unwrap().as_int().unwrap())
         }
         else if self.check_that(validate(2)) {
             let list = self.as_list().unwrap();
-            let min = *list.get(0).unwrap().as_int().unwrap();
+            let min = *list.first().unwrap().as_int().unwrap();
             let max = *list.get(1).unwrap().as_int().unwrap();
             Some(rand::thread_rng().gen_range(min, max))
         }
@@ -360,11 +487,11 @@ impl CfgValue {
             Some(*self.as_float().unwrap())
         }
         else if self.check_that(validate(1)) {
-            Some(*self.as_list().unwrap().get(0).unwrap().as_float().unwrap())
+            Some(*self.as_list().unwrap().first().unwrap().as_float().unwrap())
         }
         else if self.check_that(validate(2)) {
             let list = self.as_list().unwrap();
-            let min = *list.get(0).unwrap().as_float().unwrap();
+            let min = *list.first().unwrap().as_float().unwrap();
             let max = *list.get(1).unwrap().as_float().unwrap();
             Some(rand::thread_rng().gen_range(min, max))
         }
@@ -376,6 +503,10 @@ impl CfgValue {
     /// Returns the contents of the enum converted into an integer, if possible.
     /// 
     /// If the enum represents a float, it will be converted into an integer.
+    /// 
+    /// Note that this is a lossy cast: any fractional part is truncated, and floats
+    /// outside the integer's range saturate to its bounds (`NaN` becomes `0`). Use
+    /// `to_int_checked` to reject such values instead.
     pub fn to_int(&self) -> Option<_Int> {
         if let CfgValue::Int(x) = self {
             Some(*x)
@@ -384,6 +515,36 @@ impl CfgValue {
         } else { None }
     }
 
+    /// Returns the contents of the enum converted into an integer, if possible, without losing information.
+    /// 
+    /// This works the same as `to_int`, except that floats with a fractional part, or that
+    /// are outside the integer's range, result in `None`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// 
+    /// assert_eq!(Int(5).to_int_checked(), Some(5));
+    /// assert_eq!(Float(5.0).to_int_checked(), Some(5));
+    /// assert_eq!(Float(5.5).to_int_checked(), None);
+    /// assert_eq!(Float(1e300).to_int_checked(), None);
+    /// assert_eq!(Float(f64::NAN).to_int_checked(), None);
+    /// ```
+    pub fn to_int_checked(&self) -> Option<_Int> {
+        match self {
+            CfgValue::Int(x) => Some(*x),
+            CfgValue::Float(x) => {
+                // `_Int::MIN` is exactly representable as a float, unlike `_Int::MAX`.
+                let bound = -(_Int::MIN as _Float);
+
+                if x.fract() == 0.0 && *x >= -bound && *x < bound {
+                    Some(*x as _Int)
+                } else { None }
+            },
+            _ => None,
+        }
+    }
+
     /// Returns the contents of the enum converted into a float, if possible.
     /// 
     /// If the enum represents an integer, it will be converted into a float.
@@ -395,6 +556,378 @@ impl CfgValue {
         } else { None }
     }
 
+    /// Returns the value as a number, if it's either an `Int` or a `Float`.
+    /// 
+    /// This is meant for arithmetic over values whose exact numeric type doesn't matter, such as when
+    /// summing or averaging them. `Int`s are converted into floats, and may lose precision if they're very large.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// 
+    /// let values = vec![Int(1), Float(2.5), Str("3".into())];
+    /// let sum: f64 = values.iter().filter_map(|v| v.as_number()).sum();
+    /// 
+    /// assert_eq!(sum, 3.5);
+    /// ```
+    pub fn as_number(&self) -> Option<_Float> {
+        match self {
+            CfgValue::Int(x) => Some(*x as _Float),
+            CfgValue::Float(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    /// Returns the contents of the enum converted into a float, if possible, and if it's finite.
+    /// 
+    /// This works the same as `to_float`, except that `NaN` and infinite values result in `None`.
+    /// This prevents them from silently propagating out of the configuration.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// 
+    /// assert_eq!(Float(0.5).to_finite_float(), Some(0.5));
+    /// assert_eq!(Int(2).to_finite_float(), Some(2.0));
+    /// assert_eq!(Float(f64::NAN).to_finite_float(), None);
+    /// assert_eq!(Float(f64::INFINITY).to_finite_float(), None);
+    /// ```
+    pub fn to_finite_float(&self) -> Option<_Float> {
+        self.to_float().filter(|f| f.is_finite())
+    }
+
+    /// Returns the value coerced into its most specific type, if it's a `Str`.
+    /// 
+    /// This is useful for values which are always strings, such as ones sourced from environment variables.
+    /// The string is parsed with the following precedence:
+    /// 
+    /// - `"true"` or `"false"` becomes a `Bool`.
+    /// - Otherwise, if it can be parsed as an integer, it becomes an `Int`.
+    /// - Otherwise, if it can be parsed as a finite float, it becomes a `Float`.
+    /// - Otherwise, it's left as a `Str`.
+    /// 
+    /// All other variants are returned untouched.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// 
+    /// assert_eq!(Str("true".into()).coerce(), Bool(true));
+    /// assert_eq!(Str("42".into()).coerce(), Int(42));
+    /// assert_eq!(Str("4.2".into()).coerce(), Float(4.2));
+    /// assert_eq!(Str("hello".into()).coerce(), Str("hello".into()));
+    /// assert_eq!(Int(5).coerce(), Int(5));
+    /// ```
+    pub fn coerce(&self) -> CfgValue {
+        if let CfgValue::Str(s) = self {
+            if let Ok(b) = s.parse::<_Bool>() {
+                CfgValue::Bool(b)
+            } else if let Ok(i) = s.parse::<_Int>() {
+                CfgValue::Int(i)
+            } else if let Some(f) = s.parse::<_Float>().ok().filter(|f| f.is_finite()) {
+                CfgValue::Float(f)
+            } else {
+                self.clone()
+            }
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Returns the value as a `Duration`, if possible.
+    /// 
+    /// An `Int` is treated as an amount of seconds, and must not be negative. A `Str` can either contain
+    /// an amount of seconds, or one or more amounts followed by a unit, such as `"1h30m"`. The supported
+    /// units are `ns`, `us`, `ms`, `s`, `m`, `h` and `d`.
+    /// 
+    /// Returns `None` if the value is of another type, can't be parsed, or overflows.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// use std::time::Duration;
+    /// 
+    /// assert_eq!(Int(30).as_duration(), Some(Duration::from_secs(30)));
+    /// assert_eq!(Str("30".into()).as_duration(), Some(Duration::from_secs(30)));
+    /// assert_eq!(Str("1h30m".into()).as_duration(), Some(Duration::from_secs(5400)));
+    /// assert_eq!(Str("250ms".into()).as_duration(), Some(Duration::from_millis(250)));
+    /// 
+    /// assert_eq!(Str("5 minutes".into()).as_duration(), None);
+    /// assert_eq!(Int(-1).as_duration(), None);
+    /// ```
+    pub fn as_duration(&self) -> Option<Duration> {
+        match self {
+            CfgValue::Int(x) if *x >= 0 => Some(Duration::from_secs(*x as u64)),
+            CfgValue::Str(x) => parse_duration(x.trim()),
+            _ => None,
+        }
+    }
+
+    /// Parses the value into a `T` using its `FromStr` implementation, if it's a `Str`.
+    /// 
+    /// Returns `None` if the value isn't a `Str`, or parsing fails. This is useful for selecting enum variants by name.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// use std::str::FromStr;
+    /// 
+    /// #[derive(Debug, PartialEq)]
+    /// enum Mode { Fast, Safe }
+    /// 
+    /// impl FromStr for Mode {
+    ///     type Err = ();
+    /// 
+    ///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+    ///         match s {
+    ///             "fast" => Ok(Mode::Fast),
+    ///             "safe" => Ok(Mode::Safe),
+    ///             _ => Err(()),
+    ///         }
+    ///     }
+    /// }
+    /// 
+    /// assert_eq!(Str("safe".into()).as_enum::<Mode>(), Some(Mode::Safe));
+    /// assert_eq!(Str("slow".into()).as_enum::<Mode>(), None);
+    /// assert_eq!(Int(0).as_enum::<Mode>(), None);
+    /// ```
+    pub fn as_enum<T: std::str::FromStr>(&self) -> Option<T> {
+        self.as_str().and_then(|x| x.parse().ok())
+    }
+
+    /// Returns the boolean this value represents, accepting common textual and numeric forms.
+    /// 
+    /// Unlike `as_bool`, which only accepts a `Bool`, this is meant for configurations where booleans
+    /// may be written as strings, such as ones sourced from environment variables. The accepted forms are:
+    /// 
+    /// - `Bool(b)`, as `b`.
+    /// - `Int(1)` as `true`, and `Int(0)` as `false`.
+    /// - `Str` containing `true`, `yes`, `on` or `1` as `true`, and `false`, `no`, `off` or `0` as `false`,
+    ///   ignoring case but not surrounding whitespace.
+    /// 
+    /// Anything else returns `None`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// 
+    /// assert_eq!(Bool(false).as_bool_lenient(), Some(false));
+    /// assert_eq!(Int(1).as_bool_lenient(), Some(true));
+    /// assert_eq!(Str("Yes".into()).as_bool_lenient(), Some(true));
+    /// assert_eq!(Str("off".into()).as_bool_lenient(), Some(false));
+    /// assert_eq!(Int(2).as_bool_lenient(), None);
+    /// assert_eq!(Str("maybe".into()).as_bool_lenient(), None);
+    /// ```
+    pub fn as_bool_lenient(&self) -> Option<_Bool> {
+        match self {
+            CfgValue::Bool(b) => Some(*b),
+            CfgValue::Int(1) => Some(true),
+            CfgValue::Int(0) => Some(false),
+            CfgValue::Str(s) => match s.to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Some(true),
+                "false" | "no" | "off" | "0" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Merges `other` into this value.
+    /// 
+    /// If both values are a `Map`, they are deep-merged as with `CfgMap::merge_with`, up to this map's `max_depth`.
+    /// If both are a `List`, they are combined according to `strategy`. In any other case, including
+    /// mismatched variants such as a `Map` and an `Int`, this value is replaced by `other`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, MergeStrategy};
+    /// 
+    /// let mut list = List(vec![Int(1), Int(2)]);
+    /// list.merge(List(vec![Int(2), Int(3)]), MergeStrategy::Union);
+    /// assert_eq!(list, List(vec![Int(1), Int(2), Int(3)]));
+    /// 
+    /// let mut base = CfgMap::new();
+    /// base.add_auto("http/port", Int(80));
+    /// base.add_auto("http/host", Str("localhost".into()));
+    /// 
+    /// let mut other = CfgMap::new();
+    /// other.add_auto("http/port", Int(8080));
+    /// 
+    /// let mut map = Map(base);
+    /// map.merge(Map(other), MergeStrategy::Replace);
+    /// assert_eq!(map.get("http/port"), Some(&Int(8080)));
+    /// assert_eq!(map.get("http/host"), Some(&Str("localhost".into())));
+    /// 
+    /// let mut scalar = Int(5);
+    /// scalar.merge(Str("five".into()), MergeStrategy::Append);
+    /// assert_eq!(scalar, Str("five".into()));
+    /// ```
+    pub fn merge(&mut self, other: CfgValue, strategy: MergeStrategy) {
+        let depth = self.as_map().map_or(0, |map| map.max_depth);
+        self.merge_at(other, strategy, depth);
+    }
+
+    /// Merges `other` into this value, deep-merging maps only if `depth` is non-zero.
+    fn merge_at(&mut self, other: CfgValue, strategy: MergeStrategy, depth: usize) {
+        match (self, other) {
+            (CfgValue::Map(existing), CfgValue::Map(other)) if depth > 0 => existing.merge_at(other, strategy, depth),
+            (CfgValue::List(existing), CfgValue::List(other)) => match strategy {
+                MergeStrategy::Replace => *existing = other,
+                MergeStrategy::Append => existing.extend(other),
+                MergeStrategy::Union => {
+                    for elem in other {
+                        if !existing.contains(&elem) {
+                            existing.push(elem);
+                        }
+                    }
+                },
+            },
+            (this, other) => *this = other,
+        }
+    }
+
+    /// Visits the value using `visitor`, recursing into submaps and lists. See `CfgVisitor` for the order of calls.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgVisitor, CfgValue::*};
+    /// 
+    /// #[derive(Default)]
+    /// struct Sum(i64);
+    /// 
+    /// impl CfgVisitor for Sum {
+    ///     fn visit_int(&mut self, value: i64) { self.0 += value; }
+    /// }
+    /// 
+    /// let mut sum = Sum::default();
+    /// List(vec![Int(1), Str("2".into()), List(vec![Int(3)])]).accept(&mut sum);
+    /// assert_eq!(sum.0, 4);
+    /// ```
+    pub fn accept(&self, visitor: &mut impl CfgVisitor) {
+        visitor::visit_value(self, visitor);
+    }
+
+    /// Returns the string with leading and trailing whitespace removed, if it's a `Str`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// 
+    /// assert_eq!(Str("  localhost\n".into()).as_str_trimmed(), Some("localhost"));
+    /// assert_eq!(Int(5).as_str_trimmed(), None);
+    /// ```
+    pub fn as_str_trimmed(&self) -> Option<&str> {
+        self.as_str().map(|s| s.trim())
+    }
+
+    /// Returns references to each element of the list, if this is a `List` containing only `Map`s.
+    /// 
+    /// This is convenient for lists of configured objects, such as a list of servers. Returns `None` if
+    /// this isn't a `List`, or if any of its elements isn't a `Map`. An empty list returns an empty `Vec`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut a = CfgMap::new();
+    /// a.add("host", Str("a.com".into()));
+    /// 
+    /// let mut b = CfgMap::new();
+    /// b.add("host", Str("b.com".into()));
+    /// 
+    /// let servers = List(vec![Map(a), Map(b)]);
+    /// let hosts: Vec<_> = servers.as_map_list().unwrap().iter().filter_map(|s| s.get("host")).collect();
+    /// assert_eq!(hosts, vec![&Str("a.com".into()), &Str("b.com".into())]);
+    /// 
+    /// assert_eq!(List(vec![Map(CfgMap::new()), Int(5)]).as_map_list(), None);
+    /// assert_eq!(Map(CfgMap::new()).as_map_list(), None);
+    /// ```
+    pub fn as_map_list(&self) -> Option<Vec<&CfgMap>> {
+        self.as_list()?.iter().map(|value| value.as_map()).collect()
+    }
+
+    /// Returns the value as a `PathBuf`, if it's a `Str`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// use std::path::PathBuf;
+    /// 
+    /// assert_eq!(Str("/var/log/app.log".into()).as_path(), Some(PathBuf::from("/var/log/app.log")));
+    /// assert_eq!(Int(5).as_path(), None);
+    /// ```
+    pub fn as_path(&self) -> Option<PathBuf> {
+        self.as_str().map(PathBuf::from)
+    }
+
+    /// Returns the value as a `SocketAddr`, if it's a `Str` of the form `"ip:port"`.
+    /// 
+    /// Only literal IP addresses are accepted, with IPv6 addresses wrapped in brackets.
+    /// Hostnames such as `"localhost:80"` result in `None`, since resolving them requires a DNS lookup.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgValue::*;
+    /// use std::net::SocketAddr;
+    /// 
+    /// assert_eq!(Str("127.0.0.1:8080".into()).as_socket_addr(), Some(SocketAddr::from(([127, 0, 0, 1], 8080))));
+    /// assert!(Str("[::1]:443".into()).as_socket_addr().is_some());
+    /// 
+    /// assert_eq!(Str("127.0.0.1".into()).as_socket_addr(), None);
+    /// assert_eq!(Str("localhost:80".into()).as_socket_addr(), None);
+    /// ```
+    pub fn as_socket_addr(&self) -> Option<SocketAddr> {
+        self.as_str().and_then(|x| x.parse().ok())
+    }
+
+    /// Returns the name of the value's type, which is the name of its variant in lowercase.
+    /// 
+    /// This is useful for error messages, such as `"expected int, found str"`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// assert_eq!(Int(5).type_name(), "int");
+    /// assert_eq!(Str("hello".into()).type_name(), "str");
+    /// assert_eq!(Map(CfgMap::new()).type_name(), "map");
+    /// ```
+    pub fn type_name(&self) -> &'static str {
+        self.cfg_type().name()
+    }
+
+    /// Returns the type of the value.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgType, CfgValue::*};
+    /// 
+    /// assert_eq!(Int(5).cfg_type(), CfgType::Int);
+    /// assert_eq!(List(vec![]).cfg_type(), CfgType::List);
+    /// ```
+    pub fn cfg_type(&self) -> CfgType {
+        match self {
+            CfgValue::Int(_) => CfgType::Int,
+            CfgValue::Float(_) => CfgType::Float,
+            CfgValue::Str(_) => CfgType::Str,
+            CfgValue::Bool(_) => CfgType::Bool,
+            CfgValue::Map(_) => CfgType::Map,
+            CfgValue::List(_) => CfgType::List,
+
+            #[cfg(feature = "from_toml")]
+            CfgValue::Datetime(_) => CfgType::Datetime,
+
+            #[cfg(any(feature = "from_json", feature = "from_yaml"))]
+            CfgValue::Null => CfgType::Null,
+
+            #[cfg(feature = "from_yaml")]
+            CfgValue::BadValue => CfgType::BadValue,
+
+            #[cfg(feature = "from_yaml")]
+            CfgValue::Alias(_) => CfgType::Alias,
+        }
+    }
+
     is_type!(is_int, CfgValue::Int);
     is_type!(is_float, CfgValue::Float);
     is_type!(is_str, CfgValue::Str);
@@ -421,17 +954,121 @@ impl CfgValue {
     as_type!(as_map, CfgMap, CfgValue::Map);
     as_type!(as_list, Vec<CfgValue>, CfgValue::List);
 
+    as_list_type!(as_int_list, _Int, CfgValue::Int, |x: &_Int| *x);
+    as_list_type!(as_float_list, _Float, CfgValue::Float, |x: &_Float| *x);
+    as_list_type!(as_str_list, &str, CfgValue::Str, _Str::as_str);
+    as_list_type!(as_bool_list, _Bool, CfgValue::Bool, |x: &_Bool| *x);
+
     as_mut_type!(as_int_mut, _Int, CfgValue::Int);
     as_mut_type!(as_float_mut, _Float, CfgValue::Float);
     as_mut_type!(as_str_mut, _Str, CfgValue::Str);
     as_mut_type!(as_bool_mut, _Bool, CfgValue::Bool);
     as_mut_type!(as_map_mut, CfgMap, CfgValue::Map);
     as_mut_type!(as_list_mut, Vec<CfgValue>, CfgValue::List);
+
+    into_type!(into_int, _Int, CfgValue::Int);
+    into_type!(into_float, _Float, CfgValue::Float);
+    into_type!(into_str, _Str, CfgValue::Str);
+    into_type!(into_bool, _Bool, CfgValue::Bool);
+    into_type!(into_map, CfgMap, CfgValue::Map);
+    into_type!(into_list, Vec<CfgValue>, CfgValue::List);
+}
+
+/// Compares values of the same kind, returning `None` for values which can't be ordered.
+/// 
+/// - Numbers are compared numerically and exactly, even between an `Int` and a `Float`. `NaN` can't be ordered.
+///   Since an `Int` is never `==` to a `Float`, an `Int` is ordered before a `Float` holding the same number,
+///   so `Int(1) < Float(1.0)`. This keeps `partial_cmp` consistent with `==`.
+/// - `Str`s are compared lexicographically by their bytes, as with `String`.
+/// - `Bool`s are compared with `false` before `true`.
+/// - Any other values, including `List`s and `Map`s, only compare as `Equal` to an equal value, and can't be ordered otherwise.
+/// - Values of different kinds, such as a `Str` and an `Int`, can't be ordered.
+/// 
+/// ## Examples
+/// ```
+/// use cfgmap::CfgValue::*;
+/// 
+/// assert!(Int(1) < Float(1.5));
+/// assert!(Int(1) < Float(1.0));
+/// assert!(Str("abc".into()) < Str("abd".into()));
+/// assert!(Bool(false) < Bool(true));
+/// assert_eq!(Int(1).partial_cmp(&Str("1".into())), None);
+/// 
+/// let mut values = vec![Float(2.5), Int(-1), Int(2)];
+/// values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+/// assert_eq!(values, vec![Int(-1), Int(2), Float(2.5)]);
+/// ```
+impl PartialOrd for CfgValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (CfgValue::Int(x), CfgValue::Int(y)) => Some(x.cmp(y)),
+            (CfgValue::Str(x), CfgValue::Str(y)) => Some(x.cmp(y)),
+            (CfgValue::Bool(x), CfgValue::Bool(y)) => Some(x.cmp(y)),
+            (CfgValue::Float(x), CfgValue::Float(y)) => x.partial_cmp(y),
+            (CfgValue::Int(_), CfgValue::Float(_)) => self.cmp_loose(other).map(|ordering| ordering.then(Ordering::Less)),
+            (CfgValue::Float(_), CfgValue::Int(_)) => self.cmp_loose(other).map(|ordering| ordering.then(Ordering::Greater)),
+            _ if self == other => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
+impl CfgValue {
+    /// Compares values like `partial_cmp`, except that an `Int` and a `Float` holding the same number compare as `Equal`.
+    pub(crate) fn cmp_loose(&self, other: &CfgValue) -> Option<Ordering> {
+        match (self, other) {
+            (CfgValue::Int(x), CfgValue::Float(y)) => cmp_int_float(*x, *y),
+            (CfgValue::Float(x), CfgValue::Int(y)) => cmp_int_float(*y, *x).map(Ordering::reverse),
+            _ => self.partial_cmp(other),
+        }
+    }
+}
+
+/// Formats the value in a compact, json-like form. Submaps are formatted as with `CfgMap`, in sorted order by key.
+/// 
+/// ## Examples
+/// ```
+/// use cfgmap::CfgValue::*;
+/// 
+/// assert_eq!(Float(2.0).to_string(), "2.0");
+/// assert_eq!(List(vec![Int(1), Str("a \"b\"".into()), Bool(true)]).to_string(), r#"[1, "a \"b\"", true]"#);
+/// ```
+impl fmt::Display for CfgValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CfgValue::Int(x) => write!(f, "{}", x),
+            CfgValue::Float(x) => write!(f, "{:?}", x),
+            CfgValue::Str(x) => write!(f, "{:?}", x),
+            CfgValue::Bool(x) => write!(f, "{}", x),
+            CfgValue::Map(map) => write!(f, "{}", map),
+            CfgValue::List(list) => {
+                write!(f, "[")?;
+
+                for (index, value) in list.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", value)?;
+                }
+
+                write!(f, "]")
+            },
+            #[cfg(feature = "from_toml")]
+            CfgValue::Datetime(x) => write!(f, "{}", x),
+            #[cfg(any(feature = "from_json", feature = "from_yaml"))]
+            CfgValue::Null => write!(f, "null"),
+            #[cfg(feature = "from_yaml")]
+            CfgValue::BadValue => write!(f, "<bad value>"),
+            #[cfg(feature = "from_yaml")]
+            CfgValue::Alias(x) => write!(f, "<alias {}>", x),
+        }
+    }
 }
 
 impl conditions::Checkable for CfgValue {
     fn check_that(&self, c: conditions::Condition) -> bool {
-        return c.execute(self).to_bool();
+        c.execute(self).to_bool()
     }
 }
 
@@ -453,16 +1090,81 @@ impl conditions::Checkable for Option<&mut CfgValue> {
     }
 }
 
-fn split_once(in_string: &str, pat: char) -> (String, Option<String>) {
-    if in_string.find(pat).is_none() {
-        return (in_string.into(), None);
+/// Normalises a default path to end with a `/`, unless it is empty.
+fn normalize_default(mut path: String) -> String {
+    if !path.is_empty() && !path.ends_with('/') {
+        path.push('/');
     }
 
-    let mut splitter = in_string.splitn(2, pat);
-    let first = splitter.next().unwrap().to_string();
-    let second = splitter.next().unwrap().to_string();
+    path
+}
 
-    (first, Some(second))
+/// Parses a duration such as `"1h30m"`, or a bare amount of seconds.
+fn parse_duration(mut s: &str) -> Option<Duration> {
+    if let Ok(secs) = s.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut total = Duration::from_secs(0);
+
+    while !s.is_empty() {
+        let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let amount = s[..digits].parse::<u64>().ok()?;
+        s = &s[digits..];
+
+        let letters = s.find(|c: char| c.is_ascii_digit()).unwrap_or(s.len());
+        let unit = &s[..letters];
+        s = &s[letters..];
+
+        let part = match unit {
+            "ns" => Duration::from_nanos(amount),
+            "us" => Duration::from_micros(amount),
+            "ms" => Duration::from_millis(amount),
+            "s" => Duration::from_secs(amount),
+            "m" => Duration::from_secs(amount.checked_mul(60)?),
+            "h" => Duration::from_secs(amount.checked_mul(60 * 60)?),
+            "d" => Duration::from_secs(amount.checked_mul(24 * 60 * 60)?),
+            _ => return None,
+        };
+
+        total = total.checked_add(part)?;
+    }
+
+    Some(total)
+}
+
+/// Collects every leaf within `value` into `out`, descending into both submaps and lists, up to `depth`.
+fn collect_leaves<'a>(value: &'a CfgValue, path: String, depth: usize, out: &mut Vec<(String, &'a CfgValue)>) {
+    let join = |key: &dyn std::fmt::Display| {
+        if path.is_empty() { key.to_string() } else { format!("{}/{}", path, key) }
+    };
+
+    match value {
+        _ if depth == 0 => {},
+        CfgValue::Map(map) => map.internal_map.iter()
+            .for_each(|(key, value)| collect_leaves(value, join(key), depth - 1, out)),
+        CfgValue::List(list) => list.iter().enumerate()
+            .for_each(|(index, value)| collect_leaves(value, join(&index), depth - 1, out)),
+        _ => out.push((path, value)),
+    }
+}
+
+/// Compares an integer with a float exactly, even where the integer can't be represented as a float.
+fn cmp_int_float(x: _Int, y: _Float) -> Option<Ordering> {
+    match (x as _Float).partial_cmp(&y)? {
+        // `y` holds a whole number within the range of `i64`, or just past it, which fits within an `i128`.
+        Ordering::Equal => Some((x as i128).cmp(&(y as i128))),
+        ordering => Some(ordering),
+    }
+}
+
+/// Returns the amount of segments within a path.
+fn path_depth(path: &str) -> usize {
+    path.matches('/').count() + 1
 }
 
 fn rsplit_once(in_string: &str, pat: char) -> (Option<String>, String) {
@@ -491,35 +1193,238 @@ impl DerefMut for CfgMap {
     }
 }
 
-#[cfg(feature = "from_json")]
-impl From<Option<CfgValue>> for CfgValue {
-    fn from(opt: Option<CfgValue>) -> Self {
-        opt.unwrap_or(CfgValue::Null)
+/// Consumes the map, iterating over its top-level entries.
+impl IntoIterator for CfgMap {
+    type Item = (String, CfgValue);
+    type IntoIter = hash_map::IntoIter<String, CfgValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.internal_map.into_iter()
     }
 }
 
+/// Iterates over references to the map's top-level entries.
+impl<'a> IntoIterator for &'a CfgMap {
+    type Item = (&'a String, &'a CfgValue);
+    type IntoIter = hash_map::Iter<'a, String, CfgValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.internal_map.iter()
+    }
+}
+
+/// Builds a map from top-level entries. The `default` path of the resulting map is empty.
+impl FromIterator<(String, CfgValue)> for CfgMap {
+    fn from_iter<I: IntoIterator<Item = (String, CfgValue)>>(iter: I) -> Self {
+        CfgMap::with_hashmap(iter.into_iter().collect())
+    }
+}
+
+/// Adds top-level entries to the map, overwriting any existing ones with the same key.
+impl Extend<(String, CfgValue)> for CfgMap {
+    fn extend<I: IntoIterator<Item = (String, CfgValue)>>(&mut self, iter: I) {
+        self.internal_map.extend(iter)
+    }
+}
+
+/// Iterates over the map's top-level entries, with mutable references to the values.
+impl<'a> IntoIterator for &'a mut CfgMap {
+    type Item = (&'a String, &'a mut CfgValue);
+    type IntoIter = hash_map::IterMut<'a, String, CfgValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.internal_map.iter_mut()
+    }
+}
+
+#[cfg(feature = "from_json")]
+impl From<Option<CfgValue>> for CfgValue {
+    fn from(opt: Option<CfgValue>) -> Self {
+        opt.unwrap_or(CfgValue::Null)
+    }
+}
+
+
+/// Controls how lists are combined when merging two `CfgMap`s.
+/// 
+/// Maps found at the same path are always merged recursively, while any other values are replaced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// The list from the other map replaces the existing one.
+    #[default]
+    Replace,
+
+    /// The list from the other map is appended onto the existing one.
+    Append,
+
+    /// Elements from the other map's list are appended onto the existing one, unless already present.
+    Union,
+}
+
+/// A configuration map, containing helper functions and effectively being a wrapper
+/// around a `HashMap`s.
+/// 
+/// Two maps are equal if their contents and defaults are equal. Settings such as `max_depth` and
+/// `validate_keys`, and any callbacks registered using `on_change`, aren't compared.
+#[derive(Debug, Clone)]
+pub struct CfgMap {
+    /// An internal map representing the configuration.
+    internal_map: HashMap<String, CfgValue>,
 
-/// A configuration map, containing helper functions and effectively being a wrapper
-/// around a `HashMap`s.
-#[derive(Debug, Clone, PartialEq)]
-pub struct CfgMap {
-    /// An internal map representing the configuration.
-    internal_map: HashMap<String, CfgValue>,
-
     /// A path to the default subobject.
-    pub default: String
+    pub default: String,
+
+    /// Paths to multiple default subobjects, consulted in order.
+    /// 
+    /// If this is empty, `default` is used instead.
+    pub defaults: Vec<String>,
+
+    /// The maximum depth that recursive operations will go through.
+    /// 
+    /// Paths with more segments than this are treated as missing, and recursive traversals of the whole
+    /// tree stop descending once they reach this depth. This guards against overflowing the stack on
+    /// pathologically deep configurations, for example ones loaded from untrusted sources.
+    /// 
+    /// Only the setting of the map an operation is called on is consulted, and it isn't compared
+    /// by `==`. Defaults to `DEFAULT_MAX_DEPTH`.
+    pub max_depth: usize,
+
+    /// Whether `add`, `insert_path` and `add_auto` validate the keys within the values they insert.
+    /// 
+    /// If enabled, inserting a value containing a submap with a key such as `"a/b"` fails, since that key
+    /// would be interpreted as a path, and become unreachable using `get`. Only the setting of the map
+    /// being inserted into is consulted, and it isn't compared by `==`. Defaults to `false`.
+    pub validate_keys: bool,
+
+    /// Callbacks registered using `on_change`.
+    callbacks: Callbacks,
+}
+
+impl Default for CfgMap {
+    fn default() -> Self {
+        CfgMap::new()
+    }
+}
+
+impl PartialEq for CfgMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.internal_map == other.internal_map && self.default == other.default && self.defaults == other.defaults
+    }
+}
+
+/// Formats the map in a compact, json-like form, in sorted order by key at every level.
+/// 
+/// Since the order doesn't depend on the internal `HashMap`, the output is stable, which makes it
+/// suitable for configuration dumps and tests.
+/// 
+/// ## Examples
+/// ```
+/// use cfgmap::{CfgMap, CfgValue::*};
+/// 
+/// let mut cmap = CfgMap::new();
+/// cmap.add("port", Int(8080));
+/// cmap.add_auto("http/host", Str("localhost".into()));
+/// cmap.add("debug", Bool(true));
+/// 
+/// assert_eq!(cmap.to_string(), r#"{debug: true, http: {host: "localhost"}, port: 8080}"#);
+/// ```
+impl fmt::Display for CfgMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+
+        for (index, (key, value)) in self.iter_sorted().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}: {}", key, value)?;
+        }
+
+        write!(f, "}}")
+    }
 }
 
 impl CfgMap {
 
     /// Creates a new empty CfgMap.
     pub fn new() -> CfgMap {
-        CfgMap { internal_map: HashMap::new(), default: String::new() }
+        CfgMap { internal_map: HashMap::new(), default: String::new(), defaults: Vec::new(), max_depth: DEFAULT_MAX_DEPTH, validate_keys: false, callbacks: Callbacks::default() }
     }
 
     /// Initialises a `CfgMap` using the `map` that's passed in.
     pub fn with_hashmap(map: HashMap<String, CfgValue>) -> CfgMap {
-        CfgMap { internal_map: map, default: String::new() }
+        CfgMap { internal_map: map, ..CfgMap::new() }
+    }
+
+    /// Consumes the map, returning the internal `HashMap`. Any default paths are discarded.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// use std::collections::HashMap;
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("port", Int(8080));
+    /// 
+    /// let map: HashMap<String, _> = cmap.clone().into_inner();
+    /// assert_eq!(map.get("port"), Some(&Int(8080)));
+    /// 
+    /// assert_eq!(CfgMap::from(map), cmap);
+    /// ```
+    pub fn into_inner(self) -> HashMap<String, CfgValue> {
+        self.internal_map
+    }
+
+    /// Creates a new empty `CfgMap`, with multiple default paths.
+    /// 
+    /// When retrieving an option using `get_option`, each default path will be tried in order,
+    /// until one of them contains the option. This allows for layered defaults, for example
+    /// per-environment defaults which fall back to global ones.
+    /// 
+    /// Each path is normalised to end with a `/`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::with_defaults(vec!["env".into(), "global".into()]);
+    /// 
+    /// cmap.add("env", Map(CfgMap::new()));
+    /// cmap.add("global", Map(CfgMap::new()));
+    /// cmap.add("env/port", Int(8080));
+    /// cmap.add("global/port", Int(80));
+    /// cmap.add("global/host", Str("localhost".into()));
+    /// 
+    /// assert!(cmap.get_option("http", "port").check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.get_option("http", "host").check_that(IsExactlyStr("localhost".into())));
+    /// ```
+    pub fn with_defaults(defaults: Vec<String>) -> CfgMap {
+        let defaults = defaults.into_iter().map(normalize_default).collect();
+        CfgMap { defaults, ..CfgMap::new() }
+    }
+
+    /// Creates a new empty `CfgMap`, with space for at least `capacity` top-level entries.
+    /// 
+    /// This is useful to avoid reallocations when the size of the configuration is known beforehand.
+    pub fn with_capacity(capacity: usize) -> CfgMap {
+        CfgMap { internal_map: HashMap::with_capacity(capacity), ..CfgMap::new() }
+    }
+
+    /// Creates a new empty `CfgMap` with its default path at `default`, and space for at least `capacity` top-level entries.
+    /// 
+    /// The default path is normalised to end with a `/`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::CfgMap;
+    /// 
+    /// let cmap = CfgMap::with_default_and_capacity("default", 16);
+    /// 
+    /// assert_eq!(cmap.default, "default/");
+    /// assert!(cmap.capacity() >= 16);
+    /// ```
+    pub fn with_default_and_capacity(default: &str, capacity: usize) -> CfgMap {
+        CfgMap { default: normalize_default(default.into()), ..CfgMap::with_capacity(capacity) }
     }
 
     #[cfg(feature = "from_json")]
@@ -528,458 +1433,3504 @@ impl CfgMap {
         from_json::json_to_cfg(value)
     }
 
+    /// Consumes the map, returning an immutable `FrozenCfgMap`.
+    /// 
+    /// This is useful once a configuration has been loaded and validated, to prevent any further mutation.
+    pub fn freeze(self) -> FrozenCfgMap {
+        FrozenCfgMap::from(self)
+    }
+
+    /// Consumes the map, returning a `SharedCfgMap` which can be shared across threads and reloaded.
+    pub fn share(self) -> SharedCfgMap {
+        SharedCfgMap::new(self)
+    }
+
+    #[cfg(feature = "from_json")]
+    /// Initialises a `CfgMap` from a json string.
+    /// 
+    /// Returns a `CfgError::Parse` if the string isn't valid json, or isn't an object. If the json
+    /// itself is malformed, the error contains the line and column at which parsing failed.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let cmap = CfgMap::from_json_str(r#"{ "http": { "port": 8080 } }"#).unwrap();
+    /// assert!(cmap.get("http/port").check_that(IsExactlyInt(8080)));
+    /// 
+    /// assert!(CfgMap::from_json_str("[1, 2]").is_err());
+    /// 
+    /// match CfgMap::from_json_str("{\n  \"port\": 80,\n  invalid\n}") {
+    ///     Err(CfgError::Parse { line, column, .. }) => assert_eq!((line, column), (Some(3), Some(3))),
+    ///     _ => panic!("expected a parse error"),
+    /// }
+    /// ```
+    pub fn from_json_str(s: &str) -> Result<CfgMap, CfgError> {
+        from_json::try_json_to_cfg(serde_json::from_str(s)?)
+    }
+
+    #[cfg(feature = "from_json")]
+    /// Initialises a `CfgMap` from json read from `reader`.
+    /// 
+    /// This behaves identically to `from_json_str`, but avoids having to read the whole input into a `String`
+    /// first, which is useful for large configurations. I/O errors result in a `CfgError::Io`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let input = r#"{ "name": "cfgmap" }"#.as_bytes();
+    /// let cmap = CfgMap::from_json_reader(input).unwrap();
+    /// 
+    /// assert!(cmap.get("name").check_that(IsExactlyStr("cfgmap".into())));
+    /// ```
+    pub fn from_json_reader<R: Read>(reader: R) -> Result<CfgMap, CfgError> {
+        from_json::try_json_to_cfg(serde_json::from_reader(BufReader::new(reader))?)
+    }
+
+    #[cfg(feature = "from_json")]
+    /// Parses `patch` as json, and deep-merges it over the map, following the json merge patch format (RFC 7386).
+    /// 
+    /// - Objects within the patch are merged recursively into the existing submaps.
+    /// - `null` values within the patch remove the key from the map.
+    /// - Any other value replaces the existing one, including lists.
+    /// 
+    /// Returns a `CfgError::Parse` if the patch isn't valid json, or isn't an object. In this case, the map is left untouched.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::from_json_str(r#"{ "http": { "port": 80, "host": "localhost" }, "debug": true }"#).unwrap();
+    /// 
+    /// cmap.merge_json_str(r#"{ "http": { "port": 8080 }, "debug": null }"#).unwrap();
+    /// 
+    /// assert!(cmap.get("http/port").check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.get("http/host").check_that(IsExactlyStr("localhost".into())));
+    /// assert!(cmap.get("debug").is_none());
+    /// ```
+    pub fn merge_json_str(&mut self, patch: &str) -> Result<(), CfgError> {
+        match serde_json::from_str(patch)? {
+            JsonValue::Object(patch) => {
+                from_json::merge_patch(self, patch);
+                Ok(())
+            },
+            _ => Err(CfgError::Parse { message: "json patch passed wasn't an object".into(), line: None, column: None }),
+        }
+    }
+
+    #[cfg(feature = "from_json")]
+    /// Serializes the map into a json string. See `to_json_value` for how values are converted.
+    /// 
+    /// Keys are always sorted, so the output is deterministic.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("port", Int(8080));
+    /// cmap.add("host", Str("localhost".into()));
+    /// 
+    /// assert_eq!(cmap.to_json_string().unwrap(), r#"{"host":"localhost","port":8080}"#);
+    /// ```
+    pub fn to_json_string(&self) -> Result<String, CfgError> {
+        serde_json::to_string(&self.to_json_value()).map_err(|e| CfgError::Serialize(e.to_string()))
+    }
+
+    #[cfg(feature = "from_json")]
+    /// Serializes the map into a json string, storing its default paths under the `"$default"` key.
+    /// 
+    /// If `defaults` isn't empty, it's stored as a list of strings. Otherwise, `default` is stored as a string,
+    /// unless it's empty, in which case the key is omitted. Use `from_json_str_with_default` to read it back.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.set_default("prod");
+    /// cmap.add_auto("prod/port", Int(80));
+    /// 
+    /// let json = cmap.to_json_string_with_default().unwrap();
+    /// assert_eq!(json, r#"{"$default":"prod/","prod":{"port":80}}"#);
+    /// 
+    /// let cmap = CfgMap::from_json_str_with_default(&json).unwrap();
+    /// assert_eq!(cmap.default_path(), "prod/");
+    /// assert!(!cmap.contains_key("$default"));
+    /// ```
+    pub fn to_json_string_with_default(&self) -> Result<String, CfgError> {
+        serde_json::to_string(&from_json::cfg_to_json_with_default(self)).map_err(|e| CfgError::Serialize(e.to_string()))
+    }
+
+    #[cfg(feature = "from_json")]
+    /// Initialises a `CfgMap` from a json string, restoring its default paths from the `"$default"` key.
+    /// 
+    /// This is the inverse of `to_json_string_with_default`. A string sets `default`, and a list of strings sets `defaults`.
+    /// The key is removed from the map afterwards. If it contains anything else, it's kept as a regular value.
+    pub fn from_json_str_with_default(s: &str) -> Result<CfgMap, CfgError> {
+        let mut map = CfgMap::from_json_str(s)?;
+        from_json::take_default(&mut map);
+        Ok(map)
+    }
+
+    #[cfg(feature = "from_json")]
+    /// Serializes the map into a pretty-printed json string. See `to_json_string`.
+    pub fn to_json_string_pretty(&self) -> Result<String, CfgError> {
+        serde_json::to_string_pretty(&self.to_json_value()).map_err(|e| CfgError::Serialize(e.to_string()))
+    }
+
+    #[cfg(feature = "from_json")]
+    /// Writes the map as json into `writer`. See `to_json_value` for how values are converted.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("port", Int(8080));
+    /// 
+    /// let mut out = Vec::new();
+    /// cmap.to_json_writer(&mut out).unwrap();
+    /// 
+    /// assert_eq!(String::from_utf8(out).unwrap(), r#"{"port":8080}"#);
+    /// ```
+    pub fn to_json_writer<W: Write>(&self, writer: W) -> Result<(), CfgError> {
+        Ok(serde_json::to_writer(writer, &self.to_json_value())?)
+    }
+
+    #[cfg(feature = "from_json")]
+    /// Initialises a `CfgMap` from a reference to a json `Value`.
+    /// 
+    /// Like `from_json`, this only works if the value is an `Object`.
+    pub fn from_json_value(value: &JsonValue) -> CfgMap {
+        from_json::json_to_cfg(value.clone())
+    }
+
+    #[cfg(feature = "from_json")]
+    /// Converts the map into a json `Value`, which will always be an `Object`.
+    /// 
+    /// Each `CfgValue` is converted into its json equivalent. Floats which are `NaN` or infinite
+    /// become `null`, as json can't represent them. `Datetime`s become strings, while yaml's
+    /// `BadValue` and `Alias` become `null`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("port", Int(8080));
+    /// cmap.add("hosts", List(vec![Str("a".into()), Str("b".into())]));
+    /// 
+    /// let json = cmap.to_json_value();
+    /// 
+    /// assert_eq!(json, serde_json::json!({ "port": 8080, "hosts": ["a", "b"] }));
+    /// assert_eq!(CfgMap::from_json_value(&json), cmap);
+    /// ```
+    pub fn to_json_value(&self) -> JsonValue {
+        from_json::cfg_to_json(self)
+    }
+
     #[cfg(feature = "from_toml")]
     /// Initialises a `CfgMap` from a toml `Value`.
     pub fn from_toml(value: TomlValue) -> CfgMap {
         from_toml::toml_to_cfg(value)
     }
 
+    #[cfg(feature = "from_toml")]
+    /// Initialises a `CfgMap` from a toml string.
+    /// 
+    /// Returns a `CfgError::Parse` if the string isn't valid toml, containing the line and column at which
+    /// parsing failed, if known.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let cmap = CfgMap::from_toml_str("[http]\nport = 8080\n").unwrap();
+    /// assert!(cmap.get("http/port").check_that(IsExactlyInt(8080)));
+    /// 
+    /// let error = CfgMap::from_toml_str("[http]\nport = \n").unwrap_err();
+    /// assert!(matches!(error, CfgError::Parse { line: Some(2), .. }));
+    /// ```
+    pub fn from_toml_str(s: &str) -> Result<CfgMap, CfgError> {
+        Ok(from_toml::toml_to_cfg(toml::from_str(s)?))
+    }
+
+    #[cfg(feature = "from_toml")]
+    /// Serializes the map into a toml string.
+    /// 
+    /// Keys are always sorted, so the output is deterministic. Since toml has no null value,
+    /// any `Null`s, along with yaml's `BadValue`s and `Alias`es, are skipped.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("name", Str("cfgmap".into()));
+    /// cmap.add_auto("http/port", Int(8080));
+    /// 
+    /// assert_eq!(cmap.to_toml_string().unwrap(), "name = \"cfgmap\"\n\n[http]\nport = 8080\n");
+    /// ```
+    pub fn to_toml_string(&self) -> Result<String, CfgError> {
+        from_toml::cfg_to_toml_string(self)
+    }
+
     #[cfg(feature = "from_yaml")]
     /// Initialises a `CfgMap` from a yaml `Value`.
     pub fn from_yaml(value: YamlValue) -> CfgMap {
         from_yaml::yaml_to_cfg(value)
     }
 
-    /// Adds a new entry in the configuration.
-    /// 
-    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
-    /// get the inner submap `a/b/...y/`, and add `z` onto it. This is for convenience sake,
-    /// as doing this manually can prove to be verbose.
+    /// Initialises a `CfgMap` from an ini string.
+    /// 
+    /// - `[section]` headers become submaps, and any `key = value` lines after them are placed within.
+    ///   Lines before the first header are placed at the top level. Repeated sections are merged together.
+    /// - Values are coerced into a `Bool`, `Int` or `Float` in the same way as `CfgValue::coerce`,
+    ///   unless they're wrapped in quotes, in which case they're kept as a `Str` without the quotes.
+    /// - Lines starting with `;` or `#` are comments, and are ignored. Comments after a value aren't supported.
+    /// 
+    /// Returns a `CfgError::Parse` with the line number if a line is malformed, or a section has the same name as a top-level key.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let cmap = CfgMap::from_ini_str("
+    /// ; global options
+    /// name = cfgmap
+    /// 
+    /// [http]
+    /// port = 8080
+    /// host = \"127.0.0.1\"
+    /// 
+    /// [logging]
+    /// verbose = true
+    /// 
+    /// [http]
+    /// timeout = 2.5
+    /// ").unwrap();
+    /// 
+    /// assert!(cmap.get("name").check_that(IsExactlyStr("cfgmap".into())));
+    /// assert!(cmap.get("http/port").check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.get("http/host").check_that(IsExactlyStr("127.0.0.1".into())));
+    /// assert!(cmap.get("http/timeout").check_that(IsExactlyFloat(2.5)));
+    /// assert!(cmap.get("logging/verbose").check_that(IsTrue));
+    /// 
+    /// assert!(CfgMap::from_ini_str("[http]\nport").is_err());
+    /// ```
+    pub fn from_ini_str(s: &str) -> Result<CfgMap, CfgError> {
+        from_ini::ini_to_cfg(s)
+    }
+
+    /// Reads and parses the file at `path`, picking the format by its extension.
+    /// 
+    /// - `.json` files require the `from_json` feature, and are parsed using `from_json_str`.
+    /// - `.toml` files require the `from_toml` feature, and are parsed using `from_toml_str`.
+    /// - `.yaml` and `.yml` files require the `from_yaml` feature. Only the first document is used, which must be a hash.
+    /// 
+    /// Extensions are matched ignoring case. Returns a `CfgError::UnsupportedFormat` for any other extension,
+    /// or if the format's feature isn't enabled, a `CfgError::Io` if the file couldn't be read, and a
+    /// `CfgError::Parse` if its contents are invalid.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError};
+    /// 
+    /// assert_eq!(CfgMap::load_from_file("config.txt"), Err(CfgError::UnsupportedFormat("txt".into())));
+    /// ```
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<CfgMap, CfgError> {
+        file::load(path.as_ref())
+    }
+
+    /// Serializes the map and writes it to the file at `path`, picking the format by its extension.
+    /// 
+    /// The same formats as `load_from_file` are supported. Json is pretty-printed, and keys are always sorted.
+    /// Values which can't be represented in the format are handled as in `to_json_value` and `to_toml_string`,
+    /// while for yaml, `Datetime`s become strings, and yaml's `BadValue`s and `Alias`es become `null`.
+    /// 
+    /// Returns a `CfgError::UnsupportedFormat` for an unsupported extension, in which case no file is created.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), CfgError> {
+        file::save(self, path.as_ref())
+    }
+
+    /// Initialises a `CfgMap` from flat `(key, value)` pairs, where each key is a path separated by `separator`.
+    /// 
+    /// This is useful for integrating with flat key-value stores, and is the inverse of `flatten`.
+    /// Any intermediate submaps are created as needed. If a segment of a key was previously assigned
+    /// a value that isn't a `Map`, it is replaced by one, so later pairs take precedence.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let cmap = CfgMap::from_flat(vec![
+    ///     ("http.port".to_string(), Int(8080)),
+    ///     ("http.host".to_string(), Str("localhost".into())),
+    ///     ("debug".to_string(), Bool(true)),
+    /// ], '.');
+    /// 
+    /// assert!(cmap.get("http/port").check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.get("http/host").check_that(IsExactlyStr("localhost".into())));
+    /// assert!(cmap.get("debug").check_that(IsTrue));
+    /// ```
+    pub fn from_flat<I: IntoIterator<Item = (String, CfgValue)>>(pairs: I, separator: char) -> CfgMap {
+        let mut map = CfgMap::new();
+
+        for (key, value) in pairs {
+            let mut segments: Vec<&str> = key.split(separator).collect();
+            let last = segments.pop().unwrap_or_default();
+            let mut current = &mut map;
+
+            for segment in segments {
+                let next = current.internal_map.entry(segment.into()).or_insert_with(|| CfgValue::Map(CfgMap::new()));
+
+                if !next.is_map() {
+                    *next = CfgValue::Map(CfgMap::new());
+                }
+
+                current = next.as_map_mut().unwrap();
+            }
+
+            current.internal_map.insert(last.into(), value);
+        }
+
+        map
+    }
+
+    /// Initialises a `CfgMap` by inserting each `(path, value)` pair in order, using `insert_path`.
+    /// 
+    /// Since `insert_path` requires the parent of each path to exist, pairs are order-dependent: a submap must be
+    /// added by an earlier pair before any paths within it. Use `from_flat` to create intermediate submaps automatically.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let cmap = CfgMap::from_pairs(vec![
+    ///     ("http", Map(CfgMap::new())),
+    ///     ("http/port", Int(8080)),
+    ///     ("debug", Bool(true)),
+    /// ]).unwrap();
+    /// 
+    /// assert!(cmap.get("http/port").check_that(IsExactlyInt(8080)));
+    /// 
+    /// let error = CfgMap::from_pairs(vec![("http/port", Int(8080)), ("http", Map(CfgMap::new()))]);
+    /// assert_eq!(error, Err(CfgError::NotFound("http".into())));
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError)` if any of the pairs fails to be inserted. See `insert_path` for the possible errors.
+    /// - `Ok(CfgMap)` otherwise.
+    pub fn from_pairs(pairs: Vec<(&str, CfgValue)>) -> Result<CfgMap, CfgError> {
+        let mut map = CfgMap::new();
+
+        for (key, value) in pairs {
+            map.insert_path(key, value)?;
+        }
+
+        Ok(map)
+    }
+
+    /// Adds a new entry in the configuration.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// get the inner submap `a/b/...y/`, and add `z` onto it. This is for convenience sake,
+    /// as doing this manually can prove to be verbose.
+    /// 
+    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
+    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// 
+    /// In order to add a default value to a normal submap - you would need to do this manually,
+    /// as this function will always use `get_mut`.
+    /// 
+    /// ## Examples
+    /// 
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// 
+    /// // Works - a root add like this will always work.
+    /// assert!(cmap.add("k1", Int(5)).is_ok());
+    /// 
+    /// // Doesn't work, because k1 isn't a map.
+    /// assert!(cmap.add("k1/k2", Int(10)).is_err());
+    /// 
+    /// // Works - returns the old value.
+    /// let r = cmap.add("k1", Float(8.0));
+    /// assert_eq!(Ok(Some(Int(5))), r);
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err` if the path as specified by `key` isn't found. In the case above for example, `get_mut("a")` returns a `None`.
+    ///   This is also the case if the path has more segments than `max_depth`, or `validate_keys` is enabled and `value` contains an invalid key.
+    /// - `Ok(Some(CfgValue))` if the path as specified by key already contained a value, and was overwritten. In this case, the old value is returned.
+    /// - `Ok(None)` otherwise.
+    #[allow(clippy::result_unit_err)]
+    pub fn add(&mut self, key: &str, value: CfgValue) -> Result<Option<CfgValue>, ()> {
+        if path_depth(key) > self.max_depth || self.check_keys(key, &value).is_err() {
+            return Err(());
+        }
+
+        let fullkey = key;
+        let (path, key) = rsplit_once(key, '/');
+
+        let result = if let Some(path) = path {
+            let subtree = self.get_mut(&path);
+
+            if subtree.check_that(Condition::IsMap) {
+                subtree.unwrap().as_map_mut().unwrap().add(&key, value)
+            }
+            else {
+                Err(())
+            }
+        }
+        else {
+            Ok(self.internal_map.insert(key, value))
+        };
+
+        if result.is_ok() {
+            self.notify(fullkey);
+        }
+
+        result
+    }
+
+    /// Adds a new entry in the configuration, at the path specified by `key`.
+    /// 
+    /// This works exactly like `add`, except that it returns a descriptive `CfgError` on failure.
+    /// It should be preferred over `HashMap::insert` (available through `Deref`), which
+    /// accepts any key as-is. A key such as `"a/b"` inserted that way can't be retrieved using `get`,
+    /// since it would be interpreted as a path.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// 
+    /// assert_eq!(cmap.insert_path("sub", Map(CfgMap::new())), Ok(None));
+    /// assert_eq!(cmap.insert_path("sub/key", Int(5)), Ok(None));
+    /// assert_eq!(cmap.insert_path("sub/key", Int(6)), Ok(Some(Int(5))));
+    /// 
+    /// assert_eq!(cmap.insert_path("sub/key/inner", Int(7)), Err(CfgError::NotAMap("sub/key".into())));
+    /// assert_eq!(cmap.insert_path("other/key", Int(7)), Err(CfgError::NotFound("other".into())));
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError::NotFound)` if the parent of `key` doesn't exist.
+    /// - `Err(CfgError::NotAMap)` if the parent of `key` isn't a `Map`.
+    /// - `Err(CfgError::TooDeep)` if `key` has more segments than `max_depth`.
+    /// - `Err(CfgError::InvalidKey)` if `validate_keys` is enabled, and `value` contains a key with a `/`.
+    /// - `Ok(Some(CfgValue))` if `key` already contained a value, and was overwritten. In this case, the old value is returned.
+    /// - `Ok(None)` otherwise.
+    pub fn insert_path(&mut self, key: &str, value: CfgValue) -> Result<Option<CfgValue>, CfgError> {
+        if path_depth(key) > self.max_depth {
+            return Err(CfgError::TooDeep(key.into()));
+        }
+
+        self.check_keys(key, &value)?;
+
+        let old = match rsplit_once(key, '/') {
+            (None, key) => self.internal_map.insert(key, value),
+            (Some(path), key) => match self.get_mut(&path) {
+                Some(CfgValue::Map(map)) => map.internal_map.insert(key, value),
+                Some(_) => return Err(CfgError::NotAMap(path)),
+                None => return Err(CfgError::NotFound(path)),
+            },
+        };
+
+        self.notify(key);
+        Ok(old)
+    }
+
+    /// Adds a new entry in the configuration, creating any missing intermediate submaps along `key`.
+    /// 
+    /// This works like `add`, except that rather than failing when a submap along the path doesn't exist,
+    /// it is created - similar to `mkdir -p`. If a segment along the path exists but isn't a `Map`, this still fails.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// 
+    /// assert_eq!(cmap.add_auto("a/b/c", Int(5)), Ok(None));
+    /// assert!(cmap.get("a/b").check_that(IsMap));
+    /// assert!(cmap.get("a/b/c").check_that(IsExactlyInt(5)));
+    /// 
+    /// assert_eq!(cmap.add_auto("a/b/c/d", Int(5)), Err(CfgError::NotAMap("a/b/c".into())));
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError::NotAMap)` if a segment along the path exists, but isn't a `Map`.
+    /// - `Err(CfgError::TooDeep)` if `key` has more segments than `max_depth`.
+    /// - `Err(CfgError::InvalidKey)` if `validate_keys` is enabled, and `value` contains a key with a `/`.
+    /// - `Ok(Some(CfgValue))` if `key` already contained a value, and was overwritten. In this case, the old value is returned.
+    /// - `Ok(None)` otherwise.
+    pub fn add_auto(&mut self, key: &str, value: CfgValue) -> Result<Option<CfgValue>, CfgError> {
+        if path_depth(key) > self.max_depth {
+            return Err(CfgError::TooDeep(key.into()));
+        }
+
+        self.check_keys(key, &value)?;
+
+        let old = match rsplit_once(key, '/') {
+            (None, key) => self.internal_map.insert(key, value),
+            (Some(path), key) => self.submap_mut_or_create(&path)?.internal_map.insert(key, value),
+        };
+
+        self.notify(key);
+        Ok(old)
+    }
+
+    /// Checks the keys of any submaps within `value` if `validate_keys` is enabled, returning the path of the first invalid one.
+    fn check_keys(&self, key: &str, value: &CfgValue) -> Result<(), CfgError> {
+        fn check(path: &str, value: &CfgValue, depth: usize) -> Result<(), CfgError> {
+            if depth == 0 {
+                return Ok(());
+            }
+
+            match value {
+                CfgValue::Map(map) => map.iter().try_for_each(|(k, v)| {
+                    let path = format!("{}/{}", path, k);
+
+                    if k.contains('/') {
+                        Err(CfgError::InvalidKey(path))
+                    } else {
+                        check(&path, v, depth - 1)
+                    }
+                }),
+                CfgValue::List(list) => list.iter().enumerate().try_for_each(|(i, v)| {
+                    check(&format!("{}/{}", path, i), v, depth - 1)
+                }),
+                _ => Ok(()),
+            }
+        }
+
+        if self.validate_keys {
+            check(key, value, self.max_depth)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Gets a reference to a value from within the configuration.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
+    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// 
+    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
+    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// 
+    /// Returns `None` if the key doesn't exist, or the path has more segments than `max_depth`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// let mut submap = CfgMap::new();
+    /// 
+    /// submap.add("key", Int(5));
+    ///
+    /// cmap.add("sub", Map(submap));
+    /// 
+    /// assert!(cmap.get("sub").check_that(IsMap));
+    /// assert!(cmap.get("sub/key").check_that(IsExactlyInt(5)));
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&CfgValue> {
+        if path_depth(key) > self.max_depth {
+            return None;
+        }
+
+        self.get_segments(key.split('/'), |map, segment| map.internal_map.get(segment))
+    }
+
+    /// Gets a reference to a value from within the configuration, returning a descriptive `CfgError` on failure.
+    /// 
+    /// This resolves `key` in the same way as `get`, but distinguishes between the reasons it can fail.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(8080));
+    /// cmap.add_auto("http/hosts", List(vec![Str("a.com".into())]));
+    /// 
+    /// assert_eq!(cmap.try_get("http/port"), Ok(&Int(8080)));
+    /// assert_eq!(cmap.try_get("http/port/number"), Err(CfgError::NotAMap("http/port".into())));
+    /// assert_eq!(cmap.try_get("http/host/name"), Err(CfgError::NotFound("http/host".into())));
+    /// assert_eq!(cmap.try_get("http/hosts/1"), Err(CfgError::NotFound("http/hosts/1".into())));
+    /// assert_eq!(cmap.try_get("http/hosts/0/name"), Err(CfgError::NotAMap("http/hosts/0".into())));
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError::NotFound)` with the path up to the first segment which doesn't exist. This includes list indices
+    ///   which are out of bounds, or aren't a valid index.
+    /// - `Err(CfgError::NotAMap)` with the path of a value along `key` which can't be descended into.
+    /// - `Err(CfgError::TooDeep)` if `key` has more segments than `max_depth`.
+    /// - `Ok(&CfgValue)` otherwise.
+    pub fn try_get(&self, key: &str) -> Result<&CfgValue, CfgError> {
+        if path_depth(key) > self.max_depth {
+            return Err(CfgError::TooDeep(key.into()));
+        }
+
+        let mut segments = key.split('/');
+        let first = segments.next().unwrap_or_default();
+
+        let mut path = first.to_string();
+        let mut value = self.internal_map.get(first).ok_or_else(|| CfgError::NotFound(path.clone()))?;
+
+        // Whether `value` is an element of a list, which can only be descended into further if it's a map.
+        let mut in_list = false;
+
+        for segment in segments {
+            let next = match value {
+                CfgValue::Map(map) => {
+                    in_list = false;
+                    map.internal_map.get(segment)
+                },
+                CfgValue::List(list) if !in_list => {
+                    in_list = true;
+                    segment.parse::<usize>().ok().and_then(|index| list.get(index))
+                },
+                _ => return Err(CfgError::NotAMap(path)),
+            };
+
+            path = format!("{}/{}", path, segment);
+            value = next.ok_or_else(|| CfgError::NotFound(path.clone()))?;
+        }
+
+        Ok(value)
+    }
+
+    /// Gets a reference to a value from within the configuration, using a pre-parsed `CfgPath`.
+    /// 
+    /// This behaves identically to `get`, but avoids splitting the path again on every lookup.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgPath, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("sub/key", Int(5));
+    /// 
+    /// let path = CfgPath::new("sub/key");
+    /// 
+    /// for _ in 0..10 {
+    ///     assert!(cmap.get_path(&path).check_that(IsExactlyInt(5)));
+    /// }
+    /// ```
+    pub fn get_path(&self, path: &CfgPath) -> Option<&CfgValue> {
+        if path.segments().len() > self.max_depth {
+            return None;
+        }
+
+        self.get_segments(path.segments().iter().map(String::as_str), |map, segment| map.internal_map.get(segment))
+    }
+
+    /// Gets a reference to a value from within the configuration, matching each segment of `key` case-insensitively.
+    /// 
+    /// This works like `get`, including indexing into lists. If multiple keys within a submap match a segment,
+    /// a key matching it exactly is preferred. Otherwise, the first of the matching keys in sorted order is used.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("Http/Port", Int(8080));
+    /// cmap.add_auto("Http/PORT", Int(80));
+    /// 
+    /// assert!(cmap.get_ci("http/port").check_that(IsExactlyInt(80)));
+    /// assert!(cmap.get_ci("HTTP/Port").check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.get("http/port").is_none());
+    /// ```
+    pub fn get_ci(&self, key: &str) -> Option<&CfgValue> {
+        fn find<'a>(map: &'a CfgMap, segment: &str) -> Option<&'a CfgValue> {
+            if let Some(value) = map.internal_map.get(segment) {
+                return Some(value);
+            }
+
+            let segment = segment.to_lowercase();
+
+            map.internal_map.iter()
+                .filter(|(k, _)| k.to_lowercase() == segment)
+                .min_by(|(a, _), (b, _)| a.cmp(b))
+                .map(|(_, value)| value)
+        }
+
+        if path_depth(key) > self.max_depth {
+            return None;
+        }
+
+        self.get_segments(key.split('/'), find)
+    }
+
+    /// Walks through the segments of a path, using `find` to look up each segment within a submap.
+    fn get_segments<'a, I: Iterator<Item = &'a str>>(&self, mut segments: I, find: for<'m> fn(&'m CfgMap, &str) -> Option<&'m CfgValue>) -> Option<&CfgValue> {
+        let mut value = find(self, segments.next()?)?;
+
+        while let Some(segment) = segments.next() {
+            value = match value {
+                CfgValue::Map(map) => find(map, segment)?,
+                CfgValue::List(list) => {
+                    // If it's an invalid usize, then the whole path is invalid.
+                    let elem = list.get(segment.parse::<usize>().ok()?)?;
+
+                    // Elements can only be descended into further if they're maps.
+                    match segments.next() {
+                        Some(segment) => find(elem.as_map()?, segment)?,
+                        None => elem,
+                    }
+                },
+                _ => return None,
+            };
+        }
+
+        Some(value)
+    }
+
+    /// Gets a mutable reference to a value from within the configuration.
+    /// 
+    /// Returns `None` if the key doesn't exist, or the path has more segments than `max_depth`.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
+    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// 
+    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
+    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// let mut submap = CfgMap::new();
+    ///
+    /// cmap.add("sub", Map(submap));
+    /// 
+    /// let mut submap = cmap.get_mut("sub");
+    /// assert!(submap.check_that(IsMap));
+    /// 
+    /// submap.unwrap().as_map_mut().unwrap().add("key", Int(5));
+    /// assert!(cmap.get_mut("sub/key").check_that(IsExactlyInt(5)));
+    /// ```
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut CfgValue> {
+        if path_depth(key) > self.max_depth {
+            return None;
+        }
+
+        self.get_segments_mut(key.split('/'))
+    }
+
+    /// Gets a mutable reference to a value from within the configuration, using a pre-parsed `CfgPath`.
+    /// 
+    /// This behaves identically to `get_mut`, but avoids splitting the path again on every lookup.
+    pub fn get_path_mut(&mut self, path: &CfgPath) -> Option<&mut CfgValue> {
+        if path.segments().len() > self.max_depth {
+            return None;
+        }
+
+        self.get_segments_mut(path.segments().iter().map(String::as_str))
+    }
+
+    fn get_segments_mut<'a, I: Iterator<Item = &'a str>>(&mut self, mut segments: I) -> Option<&mut CfgValue> {
+        let mut value = self.internal_map.get_mut(segments.next()?)?;
+
+        while let Some(segment) = segments.next() {
+            value = match value {
+                CfgValue::Map(map) => map.internal_map.get_mut(segment)?,
+                CfgValue::List(list) => {
+                    let elem = list.get_mut(segment.parse::<usize>().ok()?)?;
+
+                    match segments.next() {
+                        Some(segment) => elem.as_map_mut()?.internal_map.get_mut(segment)?,
+                        None => elem,
+                    }
+                },
+                _ => return None,
+            };
+        }
+
+        Some(value)
+    }
+
+    /// Replaces the value at `key` with `value`, and returns the old value.
+    /// 
+    /// Unlike `add`, this never inserts a new entry: if `key` doesn't exist, the map is left untouched,
+    /// `value` is dropped and `None` is returned. Unlike `update_option`, no defaults are consulted.
+    /// Paths are resolved in the same way as `get_mut`, so this can replace elements within lists as well.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(80));
+    /// 
+    /// assert_eq!(cmap.replace("http/port", Int(8080)), Some(Int(80)));
+    /// assert!(cmap.get("http/port").check_that(IsExactlyInt(8080)));
+    /// 
+    /// assert_eq!(cmap.replace("http/host", Str("localhost".into())), None);
+    /// assert!(!cmap.contains_key("http/host"));
+    /// ```
+    pub fn replace(&mut self, key: &str, value: CfgValue) -> Option<CfgValue> {
+        let old = self.get_mut(key).map(|x| mem::replace(x, value))?;
+
+        self.notify(key);
+        Some(old)
+    }
+
+    /// Replaces the value at `key` with the result of calling `f` on it.
+    /// 
+    /// Paths are resolved in the same way as `get_mut`. To modify the value through a mutable reference
+    /// instead, use `get_mut` directly.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("stats/runs", Int(1));
+    /// 
+    /// cmap.modify("stats/runs", |runs| Int(runs.to_int().unwrap_or(0) + 1)).unwrap();
+    /// assert!(cmap.get("stats/runs").check_that(IsExactlyInt(2)));
+    /// 
+    /// assert_eq!(cmap.modify("stats/fails", |_| Int(0)), Err(CfgError::NotFound("stats/fails".into())));
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError::NotFound)` if the value at `key` doesn't exist. In this case, `f` isn't called.
+    /// - `Ok(())` otherwise.
+    pub fn modify<F: FnOnce(&CfgValue) -> CfgValue>(&mut self, key: &str, f: F) -> Result<(), CfgError> {
+        let value = self.get_mut(key).ok_or_else(|| CfgError::NotFound(key.into()))?;
+        *value = f(value);
+
+        self.notify(key);
+        Ok(())
+    }
+
+    /// Adds `by` to the `Int` at `key`, returning the new value.
+    /// 
+    /// This is meant for configurations used as lightweight state, such as counters. `by` may be negative.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgType, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("stats/runs", Int(1));
+    /// cmap.add("name", Str("cfgmap".into()));
+    /// 
+    /// assert_eq!(cmap.increment("stats/runs", 2), Ok(3));
+    /// assert_eq!(cmap.increment("stats/runs", -1), Ok(2));
+    /// assert_eq!(cmap.get("stats/runs"), Some(&Int(2)));
+    /// 
+    /// assert_eq!(cmap.increment("stats/fails", 1), Err(CfgError::NotFound("stats/fails".into())));
+    /// assert_eq!(
+    ///     cmap.increment("name", 1),
+    ///     Err(CfgError::TypeMismatch { path: "name".into(), expected: CfgType::Int, found: CfgType::Str })
+    /// );
+    /// 
+    /// cmap.add("max", Int(i64::MAX));
+    /// assert_eq!(cmap.increment("max", 1), Err(CfgError::Overflow("max".into())));
+    /// assert_eq!(cmap.get("max"), Some(&Int(i64::MAX)));
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError::NotFound)` if the value at `key` doesn't exist.
+    /// - `Err(CfgError::TypeMismatch)` if the value at `key` isn't an `Int`.
+    /// - `Err(CfgError::Overflow)` if the result would overflow. In this case, the value is left untouched.
+    /// - `Ok(_Int)` with the new value otherwise.
+    pub fn increment(&mut self, key: &str, by: _Int) -> Result<_Int, CfgError> {
+        let value = self.get_mut(key).ok_or_else(|| CfgError::NotFound(key.into()))?;
+
+        let current = match value {
+            CfgValue::Int(x) => x,
+            _ => return Err(CfgError::TypeMismatch { path: key.into(), expected: CfgType::Int, found: value.cfg_type() }),
+        };
+
+        *current = current.checked_add(by).ok_or_else(|| CfgError::Overflow(key.into()))?;
+        let new = *current;
+
+        self.notify(key);
+        Ok(new)
+    }
+
+    /// Appends `value` onto the end of the list at `key`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgType, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/hosts", List(vec![]));
+    /// cmap.add("name", Str("cfgmap".into()));
+    /// 
+    /// cmap.push_to("http/hosts", Str("a.com".into())).unwrap();
+    /// assert!(cmap.get("http/hosts").check_that(IsExactlyList(vec![Str("a.com".into())])));
+    /// 
+    /// assert_eq!(cmap.push_to("http/ports", Int(80)), Err(CfgError::NotFound("http/ports".into())));
+    /// assert_eq!(
+    ///     cmap.push_to("name", Int(80)),
+    ///     Err(CfgError::TypeMismatch { path: "name".into(), expected: CfgType::List, found: CfgType::Str })
+    /// );
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError::NotFound)` if the value at `key` doesn't exist.
+    /// - `Err(CfgError::TypeMismatch)` if the value at `key` isn't a `List`.
+    /// - `Ok(())` otherwise.
+    pub fn push_to(&mut self, key: &str, value: CfgValue) -> Result<(), CfgError> {
+        self.require_list_mut(key)?.push(value);
+        self.notify(key);
+        Ok(())
+    }
+
+    /// Appends every value from `values` onto the end of the list at `key`, in order.
+    /// 
+    /// This works like `push_to`, and fails in the same cases. If it fails, nothing is appended.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("ports", List(vec![Int(80)]));
+    /// 
+    /// cmap.extend_at("ports", vec![Int(443), Int(8080)]).unwrap();
+    /// assert!(cmap.get("ports").check_that(IsExactlyList(vec![Int(80), Int(443), Int(8080)])));
+    /// ```
+    pub fn extend_at<I: IntoIterator<Item = CfgValue>>(&mut self, key: &str, values: I) -> Result<(), CfgError> {
+        self.require_list_mut(key)?.extend(values);
+        self.notify(key);
+        Ok(())
+    }
+
+    fn require_list_mut(&mut self, key: &str) -> Result<&mut Vec<CfgValue>, CfgError> {
+        match self.get_mut(key) {
+            Some(CfgValue::List(list)) => Ok(list),
+            Some(value) => Err(CfgError::TypeMismatch { path: key.into(), expected: CfgType::List, found: value.cfg_type() }),
+            None => Err(CfgError::NotFound(key.into())),
+        }
+    }
+
+    /// Deletes a key from the map, and returns the value associated with it.
+    /// 
+    /// Returns `None` if the key doesn't exist.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
+    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// 
+    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
+    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    ///
+    /// cmap.add("sub", Map(CfgMap::new()));
+    /// cmap.add("sub/int", Int(5));
+    /// 
+    /// let num = cmap.remove("sub/int");
+    /// let nothing = cmap.remove("sub/nothing");
+    /// 
+    /// assert!(cmap.get("sub/int").is_none());
+    /// assert!(num.check_that(IsExactlyInt(5)));
+    /// assert!(nothing.is_none());
+    /// ```
+    pub fn remove(&mut self, key: &str) -> Option<CfgValue> {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Deletes a key from the map, and returns the value associated with it, if the value obeys the 
+    /// conditions as passed. Useful for when you want to make sure to avoid deleting another value.
+    /// 
+    /// Returns `None` if the key doesn't exist, or the value associated with the key doesn't obey the condition.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
+    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// 
+    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
+    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    ///
+    /// cmap.add("sub", Map(CfgMap::new()));
+    /// cmap.add("sub/int", Int(5));
+    /// 
+    /// let float = cmap.remove_if("sub/int", IsFloat);
+    /// assert!(cmap.get("sub/int").is_some());
+    /// assert!(float.is_none());
+    /// 
+    /// let int = cmap.remove_if("sub/int", IsInt);
+    /// assert!(cmap.get("sub/int").is_none());
+    /// assert!(int.check_that(IsExactlyInt(5)));
+    /// ```
+    pub fn remove_if(&mut self, key: &str, condition: Condition) -> Option<CfgValue> {
+        if self.get(key).check_that(condition) { self.remove(key) } else { None }
+    }
+
+    /// Deletes a key from the map, and returns the key and value associated with it.
+    /// 
+    /// Returns `None` if the key doesn't exist.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
+    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// 
+    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
+    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    ///
+    /// cmap.add("sub", Map(CfgMap::new()));
+    /// cmap.add("sub/int", Int(5));
+    /// 
+    /// let (key, num) = cmap.remove_entry("sub/int").unwrap();
+    /// let nothing = cmap.remove("sub/nothing");
+    /// 
+    /// assert!(cmap.get("sub/int").is_none());
+    /// assert_eq!(key, "int");
+    /// assert!(num.check_that(IsExactlyInt(5)));
+    /// assert!(nothing.is_none());
+    /// ```
+    pub fn remove_entry(&mut self, key: &str) -> Option<(String, CfgValue)> {
+        if path_depth(key) > self.max_depth {
+            return None;
+        }
+
+        let (path, key) = rsplit_once(key, '/');
+
+        if let Some(path) = path {
+            let subtree = self.get_mut(&path);
+
+            if subtree.check_that(Condition::IsMap) {
+                subtree.unwrap().as_map_mut().unwrap().remove_entry(&key)
+            }
+            else {
+                None
+            }
+        }
+        else {
+            self.internal_map.remove_entry(&key)
+        }
+    }
+
+    /// Deletes a key from the map, and returns the key and value associated with it, if the value obeys the 
+    /// conditions as passed. Useful for when you want to make sure to avoid deleting another value.
+    /// 
+    /// Returns `None` if the key doesn't exist, or the value associated with the key doesn't obey the condition.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
+    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// 
+    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
+    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    ///
+    /// cmap.add("sub", Map(CfgMap::new()));
+    /// cmap.add("sub/int", Int(5));
+    /// 
+    /// let float = cmap.remove_entry_if("sub/int", IsFloat);
+    /// assert!(cmap.get("sub/int").is_some());
+    /// assert!(float.is_none());
+    /// 
+    /// let (key, int) = cmap.remove_entry_if("sub/int", IsInt).unwrap();
+    /// assert!(cmap.get("sub/int").is_none());
+    /// assert_eq!(key, "int");
+    /// assert!(int.check_that(IsExactlyInt(5)));
+    /// ```
+    pub fn remove_entry_if(&mut self, key: &str, condition: Condition) -> Option<(String, CfgValue)> {
+        if self.get(key).check_that(condition) { self.remove_entry(key) } else { None }
+    }
+
+
+    /// Checks whether a certain path exists.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
+    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// 
+    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
+    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// let mut submap = CfgMap::new();
+    ///
+    /// cmap.add("num", Int(10));
+    /// submap.add("num", Int(20));
+    /// cmap.add("sub", Map(submap));
+    /// 
+    /// assert!(cmap.contains_key("num"));
+    /// assert!(cmap.contains_key("sub/num"));
+    /// ```
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Checks whether the string at `key` names an existing entry under `must_exist_under`.
+    /// 
+    /// This reads the string at `key`, and checks that `must_exist_under/<string>` exists. An empty
+    /// `must_exist_under` checks against the top-level keys instead. Returns `false` if the value at
+    /// `key` doesn't exist, or isn't a `Str`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("profiles/dev/debug", Bool(true));
+    /// cmap.add_auto("profiles/prod/debug", Bool(false));
+    /// cmap.add("active_profile", Str("dev".into()));
+    /// cmap.add("fallback_profile", Str("staging".into()));
+    /// 
+    /// assert!(cmap.validate_reference("active_profile", "profiles"));
+    /// assert!(!cmap.validate_reference("fallback_profile", "profiles"));
+    /// ```
+    pub fn validate_reference(&self, key: &str, must_exist_under: &str) -> bool {
+        let target = match self.get(key).and_then(|value| value.as_str()) {
+            Some(target) => target,
+            None => return false,
+        };
+
+        let prefix = normalize_default(must_exist_under.into());
+        self.contains_key(&format!("{}{}", prefix, target))
+    }
+
+    /// Gets a reference to a value using a JSON Pointer (RFC 6901), such as `/server/ports/0`.
+    /// 
+    /// Unlike the path syntax used by `get`, each segment is matched against keys exactly, with `~1`
+    /// and `~0` being unescaped into `/` and `~` respectively. This allows retrieving keys which contain a `/`.
+    /// Segments index into lists if the value is a `List`, as long as they're a valid index without leading zeros.
+    /// 
+    /// Returns `None` if the value doesn't exist, or the pointer is malformed. Since the empty pointer `""`
+    /// refers to the map itself rather than a value, it also returns `None`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("server", Map(CfgMap::new()));
+    /// cmap.add("server/ports", List(vec![Int(80), Int(443)]));
+    /// cmap.insert("a/b".into(), Int(1));
+    /// 
+    /// assert!(cmap.pointer("/server/ports/1").check_that(IsExactlyInt(443)));
+    /// assert!(cmap.pointer("/a~1b").check_that(IsExactlyInt(1)));
+    /// assert!(cmap.pointer("/server/ports/01").is_none());
+    /// assert!(cmap.pointer("/server/ports/+1").is_none());
+    /// assert!(cmap.pointer("server").is_none());
+    /// ```
+    pub fn pointer(&self, ptr: &str) -> Option<&CfgValue> {
+        if !ptr.starts_with('/') || path_depth(&ptr[1..]) > self.max_depth {
+            return None;
+        }
+
+        let mut segments = ptr[1..].split('/').map(|segment| segment.replace("~1", "/").replace("~0", "~"));
+        let mut current = self.internal_map.get(&segments.next()?)?;
+
+        for segment in segments {
+            current = match current {
+                CfgValue::Map(map) => map.internal_map.get(&segment)?,
+                CfgValue::List(list) => {
+                    if !segment.bytes().all(|b| b.is_ascii_digit()) || (segment.len() > 1 && segment.starts_with('0')) {
+                        return None;
+                    }
+
+                    list.get(segment.parse::<usize>().ok()?)?
+                },
+                _ => return None,
+            };
+        }
+
+        Some(current)
+    }
+
+    /// Gets a reference to the value at `key`, falling back to the same path within `defaults`.
+    /// 
+    /// Unlike `get_option`, which uses a default path within this map, this consults a separate map.
+    /// This suits applications which ship their defaults as a separate file.
+    /// 
+    /// Returns `None` if the key doesn't exist in either map.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut defaults = CfgMap::new();
+    /// defaults.add_auto("http/port", Int(80));
+    /// defaults.add_auto("http/host", Str("localhost".into()));
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(8080));
+    /// 
+    /// assert!(cmap.get_or_default("http/port", &defaults).check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.get_or_default("http/host", &defaults).check_that(IsExactlyStr("localhost".into())));
+    /// assert!(cmap.get_or_default("http/timeout", &defaults).is_none());
+    /// ```
+    pub fn get_or_default<'a>(&'a self, key: &str, defaults: &'a CfgMap) -> Option<&'a CfgValue> {
+        self.get(key).or_else(|| defaults.get(key))
+    }
+
+    /// Gets a reference to the value at `key`, only if it satisfies `condition`.
+    /// 
+    /// This is equivalent to `get` followed by `check_that`, but returns `None` for both a missing value
+    /// and one which fails the condition, which is convenient for falling back onto another value.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("port", Str("8080".into()));
+    /// cmap.add("workers", Int(4));
+    /// 
+    /// assert_eq!(cmap.get_checked("workers", IsInt & GreaterThan(0.0)), Some(&Int(4)));
+    /// assert_eq!(cmap.get_checked("port", IsInt), None);
+    /// assert_eq!(cmap.get_checked("threads", IsInt), None);
+    /// 
+    /// let port = cmap.get_checked("port", IsInt).and_then(|v| v.as_int().copied()).unwrap_or(80);
+    /// assert_eq!(port, 80);
+    /// ```
+    pub fn get_checked(&self, key: &str, condition: Condition) -> Option<&CfgValue> {
+        self.get(key).filter(|value| value.check_that(condition))
+    }
+
+    /// Gets a mutable reference to the value at `key`, first inserting the result of `f` if it doesn't exist.
+    /// 
+    /// This mirrors `HashMap`'s `entry(key).or_insert_with(f)`. `f` is only called if the value is missing,
+    /// in which case it's inserted using `add_auto`, creating any missing intermediate submaps.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("name", Str("cfgmap".into()));
+    /// 
+    /// let hosts = cmap.get_or_insert_with("http/hosts", || List(vec![])).unwrap();
+    /// hosts.as_list_mut().unwrap().push(Str("a.com".into()));
+    /// assert_eq!(cmap.get("http/hosts"), Some(&List(vec![Str("a.com".into())])));
+    /// 
+    /// let name = cmap.get_or_insert_with("name", || unreachable!()).unwrap();
+    /// assert_eq!(name, &mut Str("cfgmap".into()));
+    /// 
+    /// assert_eq!(cmap.get_or_insert_with("name/first", || Int(0)), Err(CfgError::NotAMap("name".into())));
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError)` if the value doesn't exist and couldn't be inserted. See `add_auto`.
+    /// - `Ok(&mut CfgValue)` with the existing or inserted value otherwise.
+    pub fn get_or_insert_with<F: FnOnce() -> CfgValue>(&mut self, key: &str, f: F) -> Result<&mut CfgValue, CfgError> {
+        if self.get(key).is_none() {
+            self.add_auto(key, f())?;
+        }
+
+        self.get_mut(key).ok_or_else(|| CfgError::NotFound(key.into()))
+    }
+
+    /// Gets every value whose path matches `pattern`, along with its full path.
+    /// 
+    /// The pattern uses the same path syntax as `get`, with two wildcards available as segments:
+    /// 
+    /// - `*` matches any single segment. This includes both the keys of a `Map`, and the indices of a `List`.
+    /// - `**` matches any amount of segments, including none. At the end of a pattern, it matches every descendant.
+    /// 
+    /// The results are sorted by path. Traversal stops at `max_depth`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("servers/alpha/host", Str("a.com".into()));
+    /// cmap.add_auto("servers/beta/host", Str("b.com".into()));
+    /// cmap.add_auto("servers/beta/port", Int(80));
+    /// cmap.add_auto("backup/servers/gamma/host", Str("c.com".into()));
+    /// 
+    /// let hosts = cmap.get_glob("servers/*/host");
+    /// assert_eq!(hosts, vec![
+    ///     ("servers/alpha/host".to_string(), &Str("a.com".into())),
+    ///     ("servers/beta/host".to_string(), &Str("b.com".into())),
+    /// ]);
+    /// 
+    /// let all_hosts = cmap.get_glob("**/host");
+    /// assert_eq!(all_hosts.len(), 3);
+    /// ```
+    pub fn get_glob(&self, pattern: &str) -> Vec<(String, &CfgValue)> {
+        fn children(value: &CfgValue) -> Vec<(String, &CfgValue)> {
+            match value {
+                CfgValue::Map(map) => map.internal_map.iter().map(|(k, v)| (k.clone(), v)).collect(),
+                CfgValue::List(list) => list.iter().enumerate().map(|(i, v)| (i.to_string(), v)).collect(),
+                _ => Vec::new(),
+            }
+        }
+
+        fn glob<'a>(nodes: Vec<(String, &'a CfgValue)>, pattern: &[&str], depth: usize, out: &mut Vec<(String, &'a CfgValue)>) {
+            let (first, rest) = match pattern.split_first() {
+                Some(split) if depth > 0 => split,
+                _ => return,
+            };
+
+            if *first == "**" {
+                // Match no segments at all.
+                glob(nodes.clone(), rest, depth, out);
+            }
+
+            for (path, value) in nodes {
+                if *first != "**" && *first != "*" && *first != path.rsplit('/').next().unwrap() {
+                    continue;
+                }
+
+                if rest.is_empty() {
+                    out.push((path.clone(), value));
+                }
+
+                // `**` stays in the pattern, to match more segments.
+                let next = if *first == "**" { pattern } else { rest };
+
+                let nodes = children(value).into_iter().map(|(k, v)| (format!("{}/{}", path, k), v)).collect();
+                glob(nodes, next, depth - 1, out);
+            }
+        }
+
+        let mut segments: Vec<&str> = pattern.split('/').collect();
+        segments.dedup_by(|a, b| *a == "**" && *b == "**");
+
+        let nodes = self.internal_map.iter().map(|(k, v)| (k.clone(), v)).collect();
+        let mut out = Vec::new();
+        glob(nodes, &segments, self.max_depth, &mut out);
+
+        // A path can be matched in several ways when `**` is followed by a repeated segment.
+        out.sort_by(|(a, _), (b, _)| a.cmp(b));
+        out.dedup_by(|(a, _), (b, _)| a == b);
+        out
+    }
+
+    /// Gets references to multiple values from within the configuration at once.
+    ///
+    /// The results are returned positionally, so the `n`th element of the result corresponds
+    /// to the `n`th key passed in. Each element is `None` if its key doesn't exist, exactly as with `get`.
+    /// This makes it easy to destructure a fixed set of options from the same section.
+    ///
+    /// Each key supports the same path syntax as `get`.
+    ///
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    ///
+    /// let mut cmap = CfgMap::new();
+    ///
+    /// cmap.add("server", Map(CfgMap::new()));
+    /// cmap.add("server/host", Str("localhost".into()));
+    /// cmap.add("server/port", Int(8080));
+    ///
+    /// let values = cmap.get_many(&["server/host", "server/port", "server/timeout"]);
+    ///
+    /// assert!(values[0].check_that(IsExactlyStr("localhost".into())));
+    /// assert!(values[1].check_that(IsExactlyInt(8080)));
+    /// assert!(values[2].is_none());
+    /// ```
+    pub fn get_many(&self, keys: &[&str]) -> Vec<Option<&CfgValue>> {
+        keys.iter().map(|key| self.get(key)).collect()
+    }
+
+    /// Gets the integer at `key`, or an error describing why it couldn't be retrieved.
+    /// 
+    /// This is the strict counterpart of `get_int_or`, and is useful for validating required
+    /// values at startup using `?`. The same is available for other types, such as `require_str`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgType, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("port", Int(8080));
+    /// cmap.add("host", Str("localhost".into()));
+    /// 
+    /// assert_eq!(cmap.require_int("port"), Ok(8080));
+    /// assert_eq!(cmap.require_int("workers"), Err(CfgError::NotFound("workers".into())));
+    /// assert_eq!(cmap.require_int("host"), Err(CfgError::TypeMismatch {
+    ///     path: "host".into(),
+    ///     expected: CfgType::Int,
+    ///     found: CfgType::Str,
+    /// }));
+    /// 
+    /// assert_eq!(cmap.require_int("host").unwrap_err().to_string(), "expected int at `host`, found str");
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError::NotFound)` if `key` doesn't exist.
+    /// - `Err(CfgError::TypeMismatch)` if the value at `key` isn't an `Int`.
+    /// - `Ok(_Int)` otherwise.
+    pub fn require_int(&self, key: &str) -> Result<_Int, CfgError> {
+        self.require(key, CfgType::Int, |value| value.as_int().copied())
+    }
+
+    /// Gets the float at `key`, or an error describing why it couldn't be retrieved. See `require_int`.
+    pub fn require_float(&self, key: &str) -> Result<_Float, CfgError> {
+        self.require(key, CfgType::Float, |value| value.as_float().copied())
+    }
+
+    /// Gets the string at `key`, or an error describing why it couldn't be retrieved. See `require_int`.
+    pub fn require_str(&self, key: &str) -> Result<&str, CfgError> {
+        self.require(key, CfgType::Str, |value| value.as_str().map(|s| s.as_str()))
+    }
+
+    /// Gets the bool at `key`, or an error describing why it couldn't be retrieved. See `require_int`.
+    pub fn require_bool(&self, key: &str) -> Result<_Bool, CfgError> {
+        self.require(key, CfgType::Bool, |value| value.as_bool().copied())
+    }
+
+    /// Gets the submap at `key`, or an error describing why it couldn't be retrieved. See `require_int`.
+    pub fn require_map(&self, key: &str) -> Result<&CfgMap, CfgError> {
+        self.require(key, CfgType::Map, |value| value.as_map())
+    }
+
+    /// Gets the list at `key`, or an error describing why it couldn't be retrieved. See `require_int`.
+    pub fn require_list(&self, key: &str) -> Result<&Vec<CfgValue>, CfgError> {
+        self.require(key, CfgType::List, |value| value.as_list())
+    }
+
+    fn require<'a, T, F>(&'a self, key: &str, expected: CfgType, extract: F) -> Result<T, CfgError>
+    where F: FnOnce(&'a CfgValue) -> Option<T> {
+        let value = self.get(key).ok_or_else(|| CfgError::NotFound(key.into()))?;
+
+        extract(value).ok_or_else(|| CfgError::TypeMismatch {
+            path: key.into(),
+            expected,
+            found: value.cfg_type(),
+        })
+    }
+
+    /// Gets the string at `key`, or `default` if the value doesn't exist or isn't a `Str`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("theme", Str("light".into()));
+    /// cmap.add("size", Int(12));
+    /// 
+    /// assert_eq!(cmap.get_str_or("theme", "dark"), "light");
+    /// assert_eq!(cmap.get_str_or("font", "mono"), "mono");
+    /// assert_eq!(cmap.get_str_or("size", "12"), "12");
+    /// ```
+    pub fn get_str_or<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
+        self.get(key).and_then(|value| value.as_str()).map_or(default, |s| s.as_str())
+    }
+
+    /// Gets the integer at `key`, or `default` if the value doesn't exist or isn't an `Int`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("port", Int(8080));
+    /// 
+    /// assert_eq!(cmap.get_int_or("port", 80), 8080);
+    /// assert_eq!(cmap.get_int_or("workers", 4), 4);
+    /// ```
+    pub fn get_int_or(&self, key: &str, default: _Int) -> _Int {
+        self.get(key).and_then(|value| value.as_int()).map_or(default, |i| *i)
+    }
+
+    /// Gets the float at `key`, or `default` if the value doesn't exist or isn't a `Float`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("ratio", Float(0.5));
+    /// 
+    /// assert_eq!(cmap.get_float_or("ratio", 1.0), 0.5);
+    /// assert_eq!(cmap.get_float_or("scale", 1.0), 1.0);
+    /// ```
+    pub fn get_float_or(&self, key: &str, default: _Float) -> _Float {
+        self.get(key).and_then(|value| value.as_float()).map_or(default, |f| *f)
+    }
+
+    /// Gets the bool at `key`, or `default` if the value doesn't exist or isn't a `Bool`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("verbose", Bool(true));
+    /// 
+    /// assert!(cmap.get_bool_or("verbose", false));
+    /// assert!(!cmap.get_bool_or("quiet", false));
+    /// ```
+    pub fn get_bool_or(&self, key: &str, default: _Bool) -> _Bool {
+        self.get(key).and_then(|value| value.as_bool()).map_or(default, |b| *b)
+    }
+
+    /// Gets the value at `key` as a bool, also accepting forms such as `"yes"` or `1`. See `CfgValue::as_bool_lenient`
+    /// for every accepted form.
+    /// 
+    /// Returns `None` if the value doesn't exist, or doesn't represent a bool.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("verbose", Str("true".into()));
+    /// cmap.add("color", Str("no".into()));
+    /// cmap.add("cache", Int(1));
+    /// cmap.add("name", Str("app".into()));
+    /// 
+    /// assert_eq!(cmap.get_bool_lenient("verbose"), Some(true));
+    /// assert_eq!(cmap.get_bool_lenient("color"), Some(false));
+    /// assert_eq!(cmap.get_bool_lenient("cache"), Some(true));
+    /// assert_eq!(cmap.get_bool_lenient("name"), None);
+    /// assert_eq!(cmap.get_bool_lenient("missing"), None);
+    /// ```
+    pub fn get_bool_lenient(&self, key: &str) -> Option<_Bool> {
+        self.get(key).and_then(|value| value.as_bool_lenient())
+    }
+
+    /// Sets the path to the default subobject used by `get_option`.
+    /// 
+    /// The path is normalised to end with a `/`, in the same way as `with_default_and_capacity`.
+    /// An empty path disables the default. Note that if `defaults` isn't empty, it takes precedence.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("dev/port", Int(8080));
+    /// cmap.add_auto("prod/port", Int(80));
+    /// 
+    /// let environment = "prod";
+    /// cmap.set_default(environment);
+    /// 
+    /// assert_eq!(cmap.default_path(), "prod/");
+    /// assert!(cmap.get_option("http", "port").check_that(IsExactlyInt(80)));
+    /// ```
+    pub fn set_default(&mut self, path: &str) {
+        self.default = normalize_default(path.into());
+    }
+
+    /// Returns a clone of this map, with its default paths copied from `from`.
+    /// 
+    /// Both `default` and `defaults` are copied, while the contents and any other settings are kept from this map.
+    /// This is useful for keeping the default paths intact across operations which create a new map, such as `extract`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut base = CfgMap::with_defaults(vec!["env".into(), "global".into()]);
+    /// base.set_default("global");
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("port", Int(80));
+    /// 
+    /// let copy = cmap.with_cloned_default(&base);
+    /// 
+    /// assert_eq!(copy.default_path(), "global/");
+    /// assert_eq!(copy.defaults, base.defaults);
+    /// assert_eq!(copy.get("port"), Some(&Int(80)));
+    /// ```
+    pub fn with_cloned_default(&self, from: &CfgMap) -> CfgMap {
+        let mut map = self.clone();
+
+        map.default = from.default.clone();
+        map.defaults = from.defaults.clone();
+        map
+    }
+
+    /// Returns the path to the default subobject used by `get_option`.
+    pub fn default_path(&self) -> &str {
+        &self.default
+    }
+
+    /// Gets a reference to an option within the configuration.
+    /// 
+    /// It first tries to get 
+    /// `category/option` within the normal values. If this doesn't exist, it will then 
+    /// try to retrieve `option` from the default path instead (`self.default/option`).
+    /// 
+    /// Note that if `default` wasn't set on construction, this function will instead retrieve
+    /// the value from the root directory (`option`) directly.
+    /// 
+    /// If multiple default paths were set using `with_defaults`, each of them is tried in order instead.
+    /// 
+    /// Returns `None` if the key doesn't exist in either map.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
+    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// 
+    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
+    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// let mut submap = CfgMap::new();
+    /// 
+    /// submap.add("OP1", Int(5));
+    /// cmap.add("OP1", Int(8));
+    /// 
+    /// cmap.add("sub", Map(submap));
+    /// 
+    /// assert!(cmap.get_option("sub", "OP1").check_that(IsExactlyInt(5)));
+    /// assert!(cmap.get_option("sub", "OP1").check_that(IsExactlyInt(5)));
+    /// assert!(cmap.get_option("sub", "OP2").is_none());
+    /// ```
+    pub fn get_option(&self, category: &str, option: &str) -> Option<&CfgValue> {
+        let fullkey = format!("{}/{}", category, option);
+
+        self.get(&fullkey).or_else(|| {
+            self.default_keys(option).iter().find_map(|default| self.get(default))
+        })
+    }
+
+    /// Gets the option like `get_option`, or an error naming every path that was tried if it doesn't exist.
+    /// 
+    /// This is useful for validating required options on startup.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgError, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::with_defaults(vec!["default".into()]);
+    /// cmap.add_auto("default/timeout", Int(30));
+    /// cmap.add_auto("http/port", Int(80));
+    /// 
+    /// assert_eq!(cmap.require_option("http", "port"), Ok(&Int(80)));
+    /// assert_eq!(cmap.require_option("http", "timeout"), Ok(&Int(30)));
+    /// 
+    /// let err = cmap.require_option("http", "host").unwrap_err();
+    /// assert_eq!(err, CfgError::MissingOption { path: "http/host".into(), defaults: vec!["default/host".into()] });
+    /// assert_eq!(err.to_string(), "no option found at `http/host` or at default `default/host`");
+    /// ```
+    pub fn require_option(&self, category: &str, option: &str) -> Result<&CfgValue, CfgError> {
+        self.get_option(category, option).ok_or_else(|| CfgError::MissingOption {
+            path: format!("{}/{}", category, option),
+            defaults: self.default_keys(option),
+        })
+    }
+
+    /// Updates the option with the new value `to`.
+    /// 
+    /// It first tries to get 
+    /// `category/option` within the normal values. If this doesn't exist, it will then 
+    /// try to retrieve `option` from the default path instead (`self.default/option`).
+    /// 
+    /// Note that if `default` wasn't set on construction, this function will instead retrieve
+    /// the value from the root directory (`option`) directly.
+    /// 
+    /// If multiple default paths were set using `with_defaults`, the first one containing `option` is updated.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
+    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// 
+    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
+    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// let mut submap = CfgMap::new();
+    /// 
+    /// submap.add("OP1", Int(5));
+    /// cmap.add("OP1", Int(8));
+    /// 
+    /// cmap.add("sub", Map(submap));
+    /// 
+    /// let ol1 = cmap.update_option("sub", "OP1", Int(10));
+    /// let ol2 = cmap.update_option("foo", "OP1", Int(16));
+    /// let ol3 = cmap.update_option("sub", "OP2", Int(99));
+    /// 
+    /// assert!(cmap.get_option("sub", "OP1").check_that(IsExactlyInt(10)));
+    /// assert!(cmap.get_option("foo", "OP1").check_that(IsExactlyInt(16)));
+    /// assert!(cmap.get_option("sub", "OP2").is_none());
+    /// 
+    /// assert_eq!(ol1, Some(Int(5)));
+    /// assert_eq!(ol2, Some(Int(8)));
+    /// assert_eq!(ol3, None);
+    /// ```
+    pub fn update_option(&mut self, category: &str, option: &str, to: CfgValue) -> Option<CfgValue> {
+        let key = self.option_key(category, option)?;
+        let old = self.get_mut(&key).map(|x| mem::replace(x, to));
+
+        self.notify(&key);
+        old
+    }
+
+    /// Updates multiple options at once, given as `(category, option, to)`, using `update_option`.
+    /// 
+    /// Updates are applied in order, and the old value of each is returned at the same position.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(80));
+    /// cmap.add("debug", Bool(false));
+    /// 
+    /// let old = cmap.update_options(&[
+    ///     ("http", "port", Int(8080)),
+    ///     ("http", "debug", Bool(true)),
+    ///     ("http", "missing", Int(0)),
+    /// ]);
+    /// 
+    /// assert_eq!(old, vec![Some(Int(80)), Some(Bool(false)), None]);
+    /// assert!(cmap.get("debug").check_that(IsTrue));
+    /// ```
+    pub fn update_options(&mut self, updates: &[(&str, &str, CfgValue)]) -> Vec<Option<CfgValue>> {
+        updates.iter()
+            .map(|(category, option, to)| self.update_option(category, option, to.clone()))
+            .collect()
+    }
+
+    /// Gets a mutable reference to an option within the configuration.
+    /// 
+    /// This follows the same fallback logic as `get_option`. It first tries to get
+    /// `category/option` within the normal values. If this doesn't exist, it will then 
+    /// try to retrieve `option` from the default path(s) instead.
+    /// 
+    /// Returns `None` if the key doesn't exist in either map.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// 
+    /// cmap.add("plugins", List(vec![Str("core".into())]));
+    /// cmap.add("sub", Map(CfgMap::new()));
+    /// 
+    /// cmap.get_option_mut("sub", "plugins")
+    ///     .and_then(|v| v.as_list_mut())
+    ///     .unwrap()
+    ///     .push(Str("extra".into()));
+    /// 
+    /// assert!(cmap.get("plugins").check_that(IsListWithLength(2)));
+    /// assert!(cmap.get_option_mut("sub", "missing").is_none());
+    /// ```
+    pub fn get_option_mut(&mut self, category: &str, option: &str) -> Option<&mut CfgValue> {
+        // Returning the result of `get_mut` directly in a branch would keep `self` mutably borrowed
+        // for the rest of the function, so find the path to use first, and only then borrow mutably.
+        let key = self.option_key(category, option)?;
+        self.get_mut(&key)
+    }
+
+    /// Returns the path of the value `get_option` would retrieve, if it exists.
+    fn option_key(&self, category: &str, option: &str) -> Option<String> {
+        let fullkey = format!("{}/{}", category, option);
+
+        if self.contains_key(&fullkey) {
+            Some(fullkey)
+        } else {
+            self.default_keys(option).into_iter().find(|key| self.contains_key(key))
+        }
+    }
+
+    /// Registers `callback` to be called whenever the value at `path` is written to.
+    /// 
+    /// The callback is called with the new value at `path` after a successful call to `add`, `insert_path`, `add_auto`,
+    /// `replace`, `modify`, `update_option`, `increment`, `push_to`, `extend_at`, `rename`, `merge`, `merge_with`, `fill_defaults`,
+    /// `overwrite_existing`, `apply_diff` or `apply_diff_strict`, which writes to either `path` itself, a path within it,
+    /// or a path containing it. `merge` and `merge_with` count as writing to every top-level key of the merged map. If `path` doesn't exist after the write, the callback isn't called. Modifications made
+    /// through other means, such as `get_mut`, aren't detected.
+    /// 
+    /// Callbacks aren't part of the map's contents: they're skipped when the map is cloned, and ignored when comparing maps.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// use std::sync::{Arc, Mutex};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(80));
+    /// 
+    /// let seen = Arc::new(Mutex::new(Vec::new()));
+    /// let log = seen.clone();
+    /// 
+    /// cmap.on_change("http/port", Box::new(move |value| log.lock().unwrap().push(value.clone())));
+    /// 
+    /// cmap.replace("http/port", Int(8080));
+    /// cmap.add_auto("http/host", Str("localhost".into()));
+    /// cmap.add_auto("http", Map(CfgMap::new()));
+    /// cmap.add_auto("http/port", Int(443));
+    /// 
+    /// assert_eq!(*seen.lock().unwrap(), vec![Int(8080), Int(443)]);
+    /// ```
+    pub fn on_change(&mut self, path: &str, callback: Box<dyn Fn(&CfgValue) + Send + Sync>) {
+        self.callbacks.push(path.into(), callback);
+    }
+
+    /// Calls the callbacks affected by a write to `written`.
+    fn notify(&self, written: &str) {
+        for (path, callback) in self.callbacks.affected_by(written) {
+            if let Some(value) = self.get(path) {
+                callback(value);
+            }
+        }
+    }
+
+    /// Moves the value at `from` to `to`, removing it from its original location.
+    /// 
+    /// Any missing intermediate submaps along `to` are created. This is useful for migrating
+    /// configurations whose keys have been renamed, while staying backwards compatible.
+    /// 
+    /// Both `from` and `to` can be of the form of the path `"a/b/...y/z/"`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("old_port", Int(8080));
+    /// 
+    /// assert!(cmap.rename("old_port", "http/port").is_ok());
+    /// assert!(cmap.get("old_port").is_none());
+    /// assert!(cmap.get("http/port").check_that(IsExactlyInt(8080)));
+    /// 
+    /// assert!(cmap.rename("missing", "other").is_err());
+    /// assert!(cmap.rename("http/port", "http/port/inner").is_err());
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError::NotFound)` if `from` doesn't exist.
+    /// - `Err(CfgError::NotAMap)` if `from` is an element of a list, rather than an entry of a map.
+    /// - `Err(CfgError::NotAMap)` if a segment of `to`'s parent exists, but isn't a map. In this case, nothing is moved.
+    /// - `Ok(())` otherwise.
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), CfgError> {
+        if !self.contains_key(from) {
+            return Err(CfgError::NotFound(from.into()));
+        }
+
+        if let (Some(from_parent), _) = rsplit_once(from, '/') {
+            if !self.get(&from_parent).check_that(Condition::IsMap) {
+                return Err(CfgError::NotAMap(from_parent));
+            }
+        }
+
+        let (parent, key) = rsplit_once(to, '/');
+
+        if let Some(parent) = &parent {
+            self.check_submap_path(parent)?;
+        }
+
+        let (_, value) = self.remove_entry(from).ok_or_else(|| CfgError::NotFound(from.into()))?;
+
+        let target = match parent {
+            Some(parent) => self.submap_mut_or_create(&parent)?,
+            None => self,
+        };
+
+        target.internal_map.insert(key, value);
+
+        self.notify(from);
+        self.notify(to);
+        Ok(())
+    }
+
+    /// Checks whether `submap_mut_or_create` would succeed for `path`, without modifying the map.
+    fn check_submap_path(&self, path: &str) -> Result<(), CfgError> {
+        if path_depth(path) > self.max_depth {
+            return Err(CfgError::TooDeep(path.into()));
+        }
+
+        match self.get(path) {
+            Some(CfgValue::Map(_)) => Ok(()),
+            Some(_) => Err(CfgError::NotAMap(path.into())),
+            None => match rsplit_once(path, '/').0 {
+                Some(parent) => self.check_submap_path(&parent),
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// Gets a mutable reference to the submap at `path`, creating it and any missing
+    /// intermediate submaps if needed.
+    fn submap_mut_or_create(&mut self, path: &str) -> Result<&mut CfgMap, CfgError> {
+        self.check_submap_path(path)?;
+
+        if self.contains_key(path) {
+            return self.get_mut(path)
+                .and_then(|value| value.as_map_mut())
+                .ok_or_else(|| CfgError::NotAMap(path.into()));
+        }
+
+        let (parent, key) = rsplit_once(path, '/');
+
+        let parent = match parent {
+            Some(parent) => self.submap_mut_or_create(&parent)?,
+            None => self,
+        };
+
+        match parent.internal_map.entry(key).or_insert_with(|| CfgValue::Map(CfgMap::new())) {
+            CfgValue::Map(map) => Ok(map),
+            _ => Err(CfgError::NotAMap(path.into())),
+        }
+    }
+
+    /// Deep-merges `other` into this map, using `MergeStrategy::Replace`.
+    /// 
+    /// See `merge_with` for more details.
+    pub fn merge(&mut self, other: CfgMap) {
+        self.merge_with(other, MergeStrategy::Replace)
+    }
+
+    /// Deep-merges `other` into this map.
+    /// 
+    /// For every key within `other`, if both maps contain a `Map` at that key, they are merged recursively.
+    /// If both contain a `List`, they are combined according to `strategy`. Otherwise, the value from `other`
+    /// replaces the existing one, or is inserted if it didn't exist.
+    /// 
+    /// Submaps past `max_depth` aren't merged recursively, and are instead replaced.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*, MergeStrategy};
+    /// 
+    /// let mut base = CfgMap::new();
+    /// base.add("plugins", List(vec![Str("core".into())]));
+    /// base.add("http", Map(CfgMap::new()));
+    /// base.add("http/port", Int(80));
+    /// base.add("http/host", Str("localhost".into()));
+    /// 
+    /// let mut other = CfgMap::new();
+    /// other.add("plugins", List(vec![Str("extra".into())]));
+    /// other.add("http", Map(CfgMap::new()));
+    /// other.add("http/port", Int(8080));
+    /// 
+    /// base.merge_with(other, MergeStrategy::Append);
+    /// 
+    /// assert!(base.get("plugins").check_that(IsExactlyList(vec![Str("core".into()), Str("extra".into())])));
+    /// assert!(base.get("http/port").check_that(IsExactlyInt(8080)));
+    /// assert!(base.get("http/host").check_that(IsExactlyStr("localhost".into())));
+    /// ```
+    pub fn merge_with(&mut self, other: CfgMap, strategy: MergeStrategy) {
+        let depth = self.max_depth;
+        let written: Vec<String> = other.keys().cloned().collect();
+
+        self.merge_at(other, strategy, depth);
+        written.iter().for_each(|key| self.notify(key));
+    }
+
+    fn merge_at(&mut self, other: CfgMap, strategy: MergeStrategy, depth: usize) {
+        for (key, value) in other.internal_map {
+            match self.internal_map.get_mut(&key) {
+                Some(existing) => existing.merge_at(value, strategy, depth.saturating_sub(1)),
+                None => {
+                    self.internal_map.insert(key, value);
+                },
+            }
+        }
+    }
+
+    /// Copies every value from `defaults` whose path isn't already present in the map.
+    /// 
+    /// Submaps present in both are filled recursively, and existing values are never overwritten,
+    /// even if their type differs from the default. This is meant to be called once after loading
+    /// a configuration, unlike `get_or_default` which falls back on every lookup.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut defaults = CfgMap::new();
+    /// defaults.add_auto("http/port", Int(80));
+    /// defaults.add_auto("http/host", Str("localhost".into()));
+    /// defaults.add("debug", Bool(false));
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(8080));
+    /// 
+    /// cmap.fill_defaults(&defaults);
+    /// 
+    /// assert!(cmap.get("http/port").check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.get("http/host").check_that(IsExactlyStr("localhost".into())));
+    /// assert!(cmap.get("debug").check_that(IsBool & !IsTrue));
+    /// ```
+    pub fn fill_defaults(&mut self, defaults: &CfgMap) {
+        let depth = self.max_depth;
+        let mut written = Vec::new();
+
+        self.fill_defaults_at(defaults, depth, "", &mut written);
+        written.iter().for_each(|path| self.notify(path));
+    }
+
+    /// Fills in the values missing from `defaults`, collecting the paths written to into `written`.
+    fn fill_defaults_at(&mut self, defaults: &CfgMap, depth: usize, prefix: &str, written: &mut Vec<String>) {
+        for (key, value) in &defaults.internal_map {
+            let path = format!("{}{}", prefix, key);
+
+            match (self.internal_map.get_mut(key), value) {
+                (Some(CfgValue::Map(existing)), CfgValue::Map(value)) if depth > 1 => {
+                    existing.fill_defaults_at(value, depth - 1, &format!("{}/", path), written);
+                },
+                (Some(_), _) => {},
+                (None, value) => {
+                    self.internal_map.insert(key.clone(), value.clone());
+                    written.push(path);
+                },
+            }
+        }
+    }
+
+    /// Copies every value from `other` whose path already exists in the map, ignoring any new paths.
+    /// 
+    /// This is the opposite of `fill_defaults`, and is useful for applying restricted overrides. Submaps
+    /// present in both are overwritten recursively up to `max_depth`, so new keys within them are ignored too.
+    /// Any other existing value is replaced as a whole, even if its type differs, including lists.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(80));
+    /// cmap.add_auto("http/host", Str("localhost".into()));
+    /// 
+    /// let mut overrides = CfgMap::new();
+    /// overrides.add_auto("http/port", Int(8080));
+    /// overrides.add_auto("http/tls", Bool(true));
+    /// overrides.add("debug", Bool(true));
+    /// 
+    /// cmap.overwrite_existing(&overrides);
+    /// 
+    /// assert_eq!(cmap.get("http/port"), Some(&Int(8080)));
+    /// assert_eq!(cmap.get("http/host"), Some(&Str("localhost".into())));
+    /// assert!(cmap.get("http/tls").is_none());
+    /// assert!(cmap.get("debug").is_none());
+    /// ```
+    pub fn overwrite_existing(&mut self, other: &CfgMap) {
+        let depth = self.max_depth;
+        let mut written = Vec::new();
+
+        self.overwrite_existing_at(other, depth, "", &mut written);
+        written.iter().for_each(|path| self.notify(path));
+    }
+
+    /// Overwrites the values existing within `other`, collecting the paths written to into `written`.
+    fn overwrite_existing_at(&mut self, other: &CfgMap, depth: usize, prefix: &str, written: &mut Vec<String>) {
+        for (key, value) in &other.internal_map {
+            let path = format!("{}{}", prefix, key);
+
+            match (self.internal_map.get_mut(key), value) {
+                (Some(CfgValue::Map(existing)), CfgValue::Map(value)) if depth > 1 => {
+                    existing.overwrite_existing_at(value, depth - 1, &format!("{}/", path), written);
+                },
+                (Some(existing), value) => {
+                    *existing = value.clone();
+                    written.push(path);
+                },
+                (None, _) => {},
+            }
+        }
+    }
+
+    /// Consumes the map, and returns a new one with the original placed at `key`.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case the intermediate
+    /// submaps are created as well. This is useful when composing multiple configurations into one.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("port", Int(8080));
+    /// 
+    /// let nested = cmap.nest_under("services/http");
+    /// 
+    /// assert!(nested.get("services/http").check_that(IsMap));
+    /// assert!(nested.get("services/http/port").check_that(IsExactlyInt(8080)));
+    /// ```
+    pub fn nest_under(self, key: &str) -> CfgMap {
+        key.rsplit('/').fold(self, |inner, segment| {
+            let mut outer = CfgMap::new();
+            outer.internal_map.insert(segment.into(), CfgValue::Map(inner));
+            outer
+        })
+    }
+
+    /// Returns an owned copy of the submap at `key`.
+    /// 
+    /// Returns `None` if the key doesn't exist, or the value at `key` isn't a `Map`.
+    /// 
+    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
+    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
+    /// 
+    /// The submap keeps its own default paths, rather than this map's. Use `extract_with_default` to carry them over.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("http", Map(CfgMap::new()));
+    /// cmap.add("http/port", Int(8080));
+    /// cmap.set_default("http");
+    /// 
+    /// let http = cmap.extract("http").unwrap();
+    /// 
+    /// assert!(http.get("port").check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.extract("http/port").is_none());
+    /// 
+    /// assert_eq!(http.default_path(), "");
+    /// ```
+    pub fn extract(&self, key: &str) -> Option<CfgMap> {
+        self.get(key).and_then(|value| value.as_map()).cloned()
+    }
+
+    /// Returns an owned copy of the submap at `key`, carrying over this map's default paths. See `extract`.
+    /// 
+    /// Since the submap's root is at `key`, every default path within it is made relative to `key`.
+    /// Default paths outside of it don't exist within the submap, and are dropped instead.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::with_defaults(vec!["http/global".into(), "global".into()]);
+    /// cmap.add_auto("http/global/timeout", Int(30));
+    /// cmap.add_auto("http/api/port", Int(8080));
+    /// 
+    /// let http = cmap.extract_with_default("http").unwrap();
+    /// 
+    /// assert_eq!(http.defaults, vec!["global/".to_string()]);
+    /// assert!(http.get_option("api", "timeout").check_that(IsExactlyInt(30)));
+    /// 
+    /// cmap.defaults.clear();
+    /// cmap.set_default("http/global");
+    /// assert_eq!(cmap.extract_with_default("http").unwrap().default_path(), "global/");
+    /// 
+    /// cmap.set_default("http");
+    /// assert_eq!(cmap.extract_with_default("http").unwrap().default_path(), "");
+    /// ```
+    pub fn extract_with_default(&self, key: &str) -> Option<CfgMap> {
+        let mut map = self.extract(key)?;
+        let prefix = normalize_default(key.trim_end_matches('/').into());
+
+        map.default = self.default.strip_prefix(&prefix).unwrap_or("").into();
+        map.defaults = self.defaults.iter().filter_map(|path| path.strip_prefix(&prefix)).map(String::from).collect();
+        Some(map)
+    }
+
+    /// Gets a reference to a value, using a path relative to `base`.
+    /// 
+    /// The segments of `relative` are applied onto `base` in order: `..` goes up a level, `.` stays at the
+    /// same level, and any other segment goes down into it. The resulting path is then resolved from the root
+    /// using `get`. An empty `base` refers to the root itself.
+    /// 
+    /// Returns `None` if `relative` goes up past the root, or the resulting path doesn't exist.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("services/api/port", Int(8080));
+    /// cmap.add_auto("services/web/upstream", Str("../api".into()));
+    /// 
+    /// let upstream = cmap.get("services/web/upstream").unwrap().as_str().unwrap();
+    /// 
+    /// assert!(cmap.resolve_from("services/web", &format!("{}/port", upstream)).check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.resolve_from("services", "./api/port").check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.resolve_from("services", "../../services").is_none());
+    /// ```
+    pub fn resolve_from(&self, base: &str, relative: &str) -> Option<&CfgValue> {
+        let mut segments: Vec<&str> = base.split('/').filter(|s| !s.is_empty()).collect();
+
+        for segment in relative.split('/') {
+            match segment {
+                "" | "." => {},
+                ".." => {
+                    segments.pop()?;
+                },
+                segment => segments.push(segment),
+            }
+        }
+
+        self.get(&segments.join("/"))
+    }
+
+    /// Returns the type of the value at `key`, or `None` if it doesn't exist.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgType, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(8080));
+    /// 
+    /// match cmap.path_type("http/port") {
+    ///     Some(CfgType::Int) => {},
+    ///     _ => panic!("expected an int"),
+    /// }
+    /// 
+    /// assert_eq!(cmap.path_type("http"), Some(CfgType::Map));
+    /// assert_eq!(cmap.path_type("http/host"), None);
+    /// ```
+    pub fn path_type(&self, key: &str) -> Option<CfgType> {
+        self.get(key).map(CfgValue::cfg_type)
+    }
+
+    /// Returns an owned deep copy of the value at `key`.
+    /// 
+    /// This is equivalent to `get(key).cloned()`. Returns `None` if the key doesn't exist.
+    /// Unlike `extract`, the value doesn't need to be a `Map`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/ports", List(vec![Int(80), Int(443)]));
+    /// 
+    /// let ports = cmap.clone_subtree("http/ports").unwrap();
+    /// cmap.remove("http/ports");
+    /// 
+    /// assert_eq!(ports, List(vec![Int(80), Int(443)]));
+    /// assert!(cmap.clone_subtree("http/ports").is_none());
+    /// ```
+    pub fn clone_subtree(&self, key: &str) -> Option<CfgValue> {
+        self.get(key).cloned()
+    }
+
+    /// Returns an iterator over every top-level submap, along with its key.
+    /// 
+    /// Top-level values which aren't a `Map` are skipped. The order of iteration is arbitrary.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("logger/level", Str("info".into()));
+    /// cmap.add_auto("cache/size", Int(64));
+    /// cmap.add("version", Int(2));
+    /// 
+    /// let mut names: Vec<&String> = cmap.sections().map(|(name, _)| name).collect();
+    /// names.sort();
+    /// 
+    /// assert_eq!(names, vec!["cache", "logger"]);
+    /// ```
+    pub fn sections(&self) -> impl Iterator<Item = (&String, &CfgMap)> {
+        self.internal_map.iter().filter_map(|(key, value)| value.as_map().map(|map| (key, map)))
+    }
+
+    /// Returns an iterator over every top-level submap, along with its key, allowing the submaps to be modified.
+    /// 
+    /// This works the same as `sections`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("logger/level", Str("info".into()));
+    /// cmap.add_auto("cache/size", Int(64));
+    /// 
+    /// for (_, section) in cmap.sections_mut() {
+    ///     section.add("enabled", Bool(true));
+    /// }
+    /// 
+    /// assert!(cmap.get("logger/enabled").check_that(IsTrue));
+    /// assert!(cmap.get("cache/enabled").check_that(IsTrue));
+    /// ```
+    pub fn sections_mut(&mut self) -> impl Iterator<Item = (&String, &mut CfgMap)> {
+        self.internal_map.iter_mut().filter_map(|(key, value)| value.as_map_mut().map(|map| (key, map)))
+    }
+
+    /// Returns an iterator over the top-level entries of the map, in sorted order by key.
+    /// 
+    /// Unlike `iter`, whose order depends on the internal `HashMap`, this is deterministic, which is
+    /// useful for output that should be stable, such as configuration dumps or tests.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("port", Int(8080));
+    /// cmap.add("debug", Bool(true));
+    /// cmap.add("host", Str("localhost".into()));
+    /// 
+    /// let keys: Vec<_> = cmap.iter_sorted().map(|(key, _)| key.as_str()).collect();
+    /// assert_eq!(keys, vec!["debug", "host", "port"]);
+    /// ```
+    pub fn iter_sorted(&self) -> impl Iterator<Item = (&String, &CfgValue)> {
+        let mut entries: Vec<(&String, &CfgValue)> = self.internal_map.iter().collect();
+        entries.sort_by_key(|(key, _)| *key);
+        entries.into_iter()
+    }
+
+    /// Returns every leaf within the configuration, along with its full path, with segments separated by `separator`.
+    /// 
+    /// A leaf is any value which isn't a `Map`, or an empty `Map`. Lists aren't flattened, and are returned
+    /// as leaves. Submaps at `max_depth` aren't descended into, and are returned as leaves as well.
+    /// 
+    /// The keys within each map are visited in sorted order, so the output is deterministic.
+    /// This is the inverse of `from_flat`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("http", Map(CfgMap::new()));
+    /// cmap.add("http/port", Int(8080));
+    /// cmap.add("http/host", Str("localhost".into()));
+    /// cmap.add("debug", Bool(true));
+    /// 
+    /// let flat = cmap.flatten('.');
+    /// assert_eq!(flat, vec![
+    ///     ("debug".to_string(), &Bool(true)),
+    ///     ("http.host".to_string(), &Str("localhost".into())),
+    ///     ("http.port".to_string(), &Int(8080)),
+    /// ]);
+    /// 
+    /// let owned = flat.into_iter().map(|(k, v)| (k, v.clone()));
+    /// assert_eq!(CfgMap::from_flat(owned, '.'), cmap);
+    /// ```
+    pub fn flatten(&self, separator: char) -> Vec<(String, &CfgValue)> {
+        fn flatten_into<'a>(map: &'a CfgMap, prefix: &str, separator: char, depth: usize, out: &mut Vec<(String, &'a CfgValue)>) {
+            for (key, value) in map.iter_sorted() {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}{}{}", prefix, separator, key) };
+
+                match value {
+                    CfgValue::Map(inner) if depth > 1 && !inner.is_empty() => {
+                        flatten_into(inner, &path, separator, depth - 1, out)
+                    },
+                    _ => out.push((path, value)),
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        flatten_into(self, "", separator, self.max_depth, &mut out);
+        out
+    }
+
+    /// Returns every scalar within the configuration as an environment variable, for passing to child processes.
+    /// 
+    /// This is the inverse of `CfgBuilder::with_env`. The map is flattened as in `flatten`, and each path is uppercased,
+    /// with segments separated by `__` and prefixed by `prefix` followed by a `_`. So with the prefix `APP`, the value
+    /// at `http/port` becomes `APP_HTTP__PORT`. Pairs are returned sorted by their path.
+    /// 
+    /// Values are converted into strings as follows:
+    /// 
+    /// - `Str`s are kept as they are.
+    /// - `Int`s and `Bool`s use their usual representation, such as `8080` or `true`.
+    /// - `Float`s always contain a decimal point, such as `2.0`, so that they're coerced back into a `Float`.
+    /// - `Datetime`s use their toml representation.
+    /// - `List`s, empty `Map`s, and `Null`s can't be represented, and are skipped, along with yaml's `BadValue`s and `Alias`es.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgBuilder, CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(8080));
+    /// cmap.add_auto("http/ratio", Float(2.0));
+    /// cmap.add("debug", Bool(true));
+    /// cmap.add("tags", List(vec![Str("a".into())]));
+    /// 
+    /// let pairs = cmap.to_env_pairs("ENV_PAIRS_DOC");
+    /// 
+    /// assert_eq!(pairs, vec![
+    ///     ("ENV_PAIRS_DOC_DEBUG".to_string(), "true".to_string()),
+    ///     ("ENV_PAIRS_DOC_HTTP__PORT".to_string(), "8080".to_string()),
+    ///     ("ENV_PAIRS_DOC_HTTP__RATIO".to_string(), "2.0".to_string()),
+    /// ]);
+    /// 
+    /// for (name, value) in pairs {
+    ///     std::env::set_var(name, value);
+    /// }
+    /// 
+    /// let read = CfgBuilder::new().with_env("ENV_PAIRS_DOC").build().unwrap();
+    /// cmap.remove("tags");
+    /// assert_eq!(read, cmap);
+    /// ```
+    pub fn to_env_pairs(&self, prefix: &str) -> Vec<(String, String)> {
+        self.flatten('/')
+            .into_iter()
+            .filter_map(|(path, value)| {
+                let value = match value {
+                    CfgValue::Str(x) => x.clone(),
+                    CfgValue::Int(x) => x.to_string(),
+                    CfgValue::Float(x) => format!("{:?}", x),
+                    CfgValue::Bool(x) => x.to_string(),
+
+                    #[cfg(feature = "from_toml")]
+                    CfgValue::Datetime(x) => x.to_string(),
+
+                    _ => return None,
+                };
+
+                let name = path.replace('/', "__").to_uppercase();
+                Some((format!("{}_{}", prefix, name), value))
+            })
+            .collect()
+    }
+
+    /// Returns the full path of every leaf within the configuration which satisfies `condition`.
+    /// 
+    /// Both submaps and lists are descended into, so a leaf is any value which isn't a `Map` or a `List`.
+    /// Elements within lists are returned with their index as a segment, for example `servers/0/host`.
+    /// Traversal stops at `max_depth`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("name", Str("".into()));
+    /// cmap.add("http", Map(CfgMap::new()));
+    /// cmap.add("http/host", Str("".into()));
+    /// cmap.add("http/port", Int(8080));
+    /// 
+    /// let mut empty = cmap.find_all(IsExactlyStr("".into()));
+    /// empty.sort();
+    /// 
+    /// assert_eq!(empty, vec!["http/host", "name"]);
+    /// ```
+    pub fn find_all(&self, condition: Condition) -> Vec<String> {
+        self.leaves()
+            .into_iter()
+            .filter(|(_, value)| value.check_that(condition.clone()))
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// Returns the amount of leaves within the configuration which satisfy `condition`.
+    /// 
+    /// Leaves are found in the same way as `find_all`, descending into both submaps and lists.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("ports", List(vec![Int(80), Int(443)]));
+    /// cmap.add_auto("http/timeout", Int(30));
+    /// cmap.add("name", Str("cfgmap".into()));
+    /// 
+    /// assert_eq!(cmap.count_matching(IsInt), 3);
+    /// ```
+    pub fn count_matching(&self, condition: Condition) -> usize {
+        self.leaves().into_iter().filter(|(_, value)| value.check_that(condition.clone())).count()
+    }
+
+    /// Checks whether any leaf within the configuration satisfies `condition`.
+    /// 
+    /// Leaves are found in the same way as `find_all`, descending into both submaps and lists.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("ports", List(vec![Int(80), Str("443".into())]));
+    /// 
+    /// assert!(cmap.any_matching(IsStr));
+    /// assert!(!cmap.any_matching(IsFloat));
+    /// ```
+    pub fn any_matching(&self, condition: Condition) -> bool {
+        self.leaves().into_iter().any(|(_, value)| value.check_that(condition.clone()))
+    }
+
+    /// Checks whether every leaf within the configuration satisfies `condition`.
+    /// 
+    /// Leaves are found in the same way as `find_all`, descending into both submaps and lists.
+    /// Returns `true` if the configuration contains no leaves at all.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("ports", List(vec![Int(80), Int(443)]));
+    /// cmap.add_auto("http/timeout", Int(30));
+    /// 
+    /// assert!(cmap.all_leaves_matching(IsInt));
+    /// 
+    /// cmap.add("name", Str("cfgmap".into()));
+    /// assert!(!cmap.all_leaves_matching(IsInt));
+    /// ```
+    pub fn all_leaves_matching(&self, condition: Condition) -> bool {
+        self.leaves().into_iter().all(|(_, value)| value.check_that(condition.clone()))
+    }
+
+    /// Checks each path within `rules` against its condition, returning the paths which failed, in order.
+    /// 
+    /// A path which doesn't exist always fails, regardless of its condition. This is useful for running a batch
+    /// of checks on startup, and reporting every problem at once instead of stopping at the first one.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(80));
+    /// cmap.add_auto("http/host", Str("".into()));
+    /// 
+    /// let failed = cmap.validate_conditions(&[
+    ///     ("http/port", IsInt & GreaterThan(0.0)),
+    ///     ("http/host", IsStr & !IsExactlyStr("".into())),
+    ///     ("http/timeout", IsInt),
+    /// ]);
+    /// 
+    /// assert_eq!(failed, vec!["http/host", "http/timeout"]);
+    /// ```
+    pub fn validate_conditions(&self, rules: &[(&str, Condition)]) -> Vec<String> {
+        rules.iter()
+            .filter(|(path, condition)| !self.get(path).map_or(false, |value| value.check_that(condition.clone())))
+            .map(|(path, _)| path.to_string())
+            .collect()
+    }
+
+    /// Checks whether the numbers at `path_a` and `path_b` satisfy `relation`, in that order.
+    /// 
+    /// This allows for constraints between values, such as a maximum being at least its minimum.
+    /// Numbers are compared exactly by value, so an `Int` and a `Float` holding the same number are equal.
+    /// Returns `false` if either value doesn't exist or isn't a number, or if either is `NaN`.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Relation};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("pool/min", Int(2));
+    /// cmap.add_auto("pool/max", Float(8.0));
+    /// cmap.add("name", Str("8".into()));
+    /// 
+    /// assert!(cmap.check_relation("pool/max", Relation::Ge, "pool/min"));
+    /// assert!(cmap.check_relation("pool/min", Relation::Lt, "pool/max"));
+    /// assert!(!cmap.check_relation("pool/min", Relation::Eq, "pool/max"));
+    /// 
+    /// cmap.add("pool/initial", Float(2.0));
+    /// assert!(cmap.check_relation("pool/min", Relation::Eq, "pool/initial"));
+    /// 
+    /// assert!(!cmap.check_relation("pool/max", Relation::Eq, "name"));
+    /// assert!(!cmap.check_relation("pool/max", Relation::Ne, "pool/step"));
+    /// ```
+    pub fn check_relation(&self, path_a: &str, relation: Relation, path_b: &str) -> bool {
+        let (a, b) = match (self.get(path_a), self.get(path_b)) {
+            (Some(a), Some(b)) if a.as_number().is_some() && b.as_number().is_some() => (a, b),
+            _ => return false,
+        };
+
+        a.cmp_loose(b).map_or(false, |ordering| relation.holds(ordering))
+    }
+
+    /// Removes every leaf within the configuration which satisfies `condition`, returning the amount removed.
+    /// 
+    /// This is the inverse of `find_all`, and descends into submaps and lists in the same way, up to `max_depth`.
+    /// Matching elements within lists are removed from the list. Any submaps which become empty as a result
+    /// are removed as well, while submaps which were already empty are kept.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("name", Str("".into()));
+    /// cmap.add("tags", List(vec![Str("".into()), Str("a".into())]));
+    /// cmap.add("http", Map(CfgMap::new()));
+    /// cmap.add("http/host", Str("".into()));
+    /// 
+    /// assert_eq!(cmap.remove_matching(IsExactlyStr("".into())), 3);
+    /// 
+    /// assert!(cmap.get("name").is_none());
+    /// assert!(cmap.get("http").is_none());
+    /// assert_eq!(cmap.get("tags"), Some(&List(vec![Str("a".into())])));
+    /// ```
+    pub fn remove_matching(&mut self, condition: Condition) -> usize {
+        // Returns whether `value` should be kept.
+        fn retain_value(value: &mut CfgValue, condition: &Condition, depth: usize, removed: &mut usize) -> bool {
+            match value {
+                _ if depth == 0 => true,
+                CfgValue::Map(map) => {
+                    let was_empty = map.is_empty();
+                    map.internal_map.retain(|_, v| retain_value(v, condition, depth - 1, removed));
+                    was_empty || !map.is_empty()
+                },
+                CfgValue::List(list) => {
+                    list.retain_mut(|v| retain_value(v, condition, depth - 1, removed));
+                    true
+                },
+                _ if value.check_that(condition.clone()) => {
+                    *removed += 1;
+                    false
+                },
+                _ => true,
+            }
+        }
+
+        let mut removed = 0;
+        let depth = self.max_depth;
+        self.internal_map.retain(|_, v| retain_value(v, &condition, depth, &mut removed));
+        removed
+    }
+
+    #[cfg(any(feature = "from_json", feature = "from_yaml"))]
+    /// Removes every `Null` within the configuration, returning the amount removed.
+    /// 
+    /// This descends into submaps and lists up to `max_depth`, removing `Null` elements from lists as well.
+    /// Any submaps or lists which become empty as a result are removed too, although they aren't counted,
+    /// while ones which were already empty are kept. This is useful for cleaning up after a merge patch.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("name", Null);
+    /// cmap.add("tags", List(vec![Null, Str("a".into())]));
+    /// cmap.add("empty", List(vec![]));
+    /// cmap.add_auto("http/host", Null);
+    /// 
+    /// assert_eq!(cmap.strip_nulls(), 3);
+    /// 
+    /// assert!(cmap.get("name").is_none());
+    /// assert!(cmap.get("http").is_none());
+    /// assert_eq!(cmap.get("tags"), Some(&List(vec![Str("a".into())])));
+    /// assert_eq!(cmap.get("empty"), Some(&List(vec![])));
+    /// ```
+    pub fn strip_nulls(&mut self) -> usize {
+        // Returns whether `value` should be kept.
+        fn retain_value(value: &mut CfgValue, depth: usize, removed: &mut usize) -> bool {
+            match value {
+                _ if depth == 0 => true,
+                CfgValue::Map(map) => {
+                    let was_empty = map.is_empty();
+                    map.internal_map.retain(|_, v| retain_value(v, depth - 1, removed));
+                    was_empty || !map.is_empty()
+                },
+                CfgValue::List(list) => {
+                    let was_empty = list.is_empty();
+                    list.retain_mut(|v| retain_value(v, depth - 1, removed));
+                    was_empty || !list.is_empty()
+                },
+                CfgValue::Null => {
+                    *removed += 1;
+                    false
+                },
+                _ => true,
+            }
+        }
+
+        let mut removed = 0;
+        let depth = self.max_depth;
+        self.internal_map.retain(|_, v| retain_value(v, depth, &mut removed));
+        removed
+    }
+
+    /// Removes every empty `Str`, `List` and `Map` within the configuration, returning the amount removed.
+    /// 
+    /// This descends into submaps and lists up to `max_depth`, removing empty elements from lists as well.
+    /// Submaps and lists which become empty as a result are removed too, and are included in the count.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("name", Str("".into()));
+    /// cmap.add("tags", List(vec![Str("".into()), Str("a".into())]));
+    /// cmap.add("plugins", List(vec![]));
+    /// cmap.add_auto("http/host", Str("".into()));
+    /// cmap.add("port", Int(0));
+    /// 
+    /// // `name`, `plugins`, `http/host`, `http`, and the first element of `tags`.
+    /// assert_eq!(cmap.strip_empty(), 5);
+    /// 
+    /// assert_eq!(cmap.len(), 2);
+    /// assert_eq!(cmap.get("tags"), Some(&List(vec![Str("a".into())])));
+    /// assert_eq!(cmap.get("port"), Some(&Int(0)));
+    /// ```
+    pub fn strip_empty(&mut self) -> usize {
+        // Returns whether `value` should be kept.
+        fn retain_value(value: &mut CfgValue, depth: usize, removed: &mut usize) -> bool {
+            let keep = match value {
+                _ if depth == 0 => true,
+                CfgValue::Map(map) => {
+                    map.internal_map.retain(|_, v| retain_value(v, depth - 1, removed));
+                    !map.is_empty()
+                },
+                CfgValue::List(list) => {
+                    list.retain_mut(|v| retain_value(v, depth - 1, removed));
+                    !list.is_empty()
+                },
+                CfgValue::Str(s) => !s.is_empty(),
+                _ => true,
+            };
+
+            if !keep {
+                *removed += 1;
+            }
+
+            keep
+        }
+
+        let mut removed = 0;
+        let depth = self.max_depth;
+        self.internal_map.retain(|_, v| retain_value(v, depth, &mut removed));
+        removed
+    }
+
+    /// Deeply compares the contents of this map with `other`, skipping the paths within `ignore`.
+    /// 
+    /// This is useful in tests, where certain values such as timestamps or generated ids may differ.
+    /// Paths within `ignore` which don't exist in either map are simply skipped. Only the contents
+    /// of the maps are compared, so their `default` paths may differ.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut a = CfgMap::new();
+    /// a.add("name", Str("app".into()));
+    /// a.add("meta", Map(CfgMap::new()));
+    /// a.add("meta/generated_at", Int(100));
+    /// 
+    /// let mut b = a.clone();
+    /// b.add("meta/generated_at", Int(200));
+    /// 
+    /// assert!(a != b);
+    /// assert!(a.equals_ignoring(&b, &["meta/generated_at"]));
+    /// assert!(!a.equals_ignoring(&b, &["name"]));
+    /// ```
+    pub fn equals_ignoring(&self, other: &CfgMap, ignore: &[&str]) -> bool {
+        let mut this = self.clone();
+        let mut other = other.clone();
+
+        for path in ignore {
+            this.remove(path);
+            other.remove(path);
+        }
+
+        this.internal_map == other.internal_map
+    }
+
+    /// Checks whether every value within this map exists in `other` at the same path, with an equal value.
+    /// 
+    /// Submaps are compared recursively up to `max_depth`, so `other` may contain extra keys at any level.
+    /// Any other values, including lists, must be exactly equal. This is useful in tests, to assert on
+    /// only the parts of a configuration that matter.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut actual = CfgMap::new();
+    /// actual.add_auto("http/port", Int(8080));
+    /// actual.add_auto("http/host", Str("localhost".into()));
+    /// actual.add("tags", List(vec![Str("a".into()), Str("b".into())]));
+    /// 
+    /// let mut expected = CfgMap::new();
+    /// expected.add_auto("http/port", Int(8080));
+    /// assert!(expected.is_subset_of(&actual));
+    /// assert!(!actual.is_subset_of(&expected));
+    /// 
+    /// expected.add("tags", List(vec![Str("a".into())]));
+    /// assert!(!expected.is_subset_of(&actual));
+    /// ```
+    pub fn is_subset_of(&self, other: &CfgMap) -> bool {
+        fn subset(a: &CfgMap, b: &CfgMap, depth: usize) -> bool {
+            a.iter().all(|(key, value)| match (value, b.internal_map.get(key)) {
+                (CfgValue::Map(a), Some(CfgValue::Map(b))) if depth > 1 => subset(a, b, depth - 1),
+                (_, Some(other)) => value == other,
+                (_, None) => false,
+            })
+        }
+
+        subset(self, other, self.max_depth)
+    }
+
+    /// Returns a hash of the contents of the map, which can be stored and compared to detect changes.
+    /// 
+    /// Keys are hashed in sorted order, so the hash doesn't depend on the iteration order of the internal `HashMap`.
+    /// The hash is stable across runs and platforms, and only changes between versions of this crate if noted in
+    /// the changelog. Maps which are equal using `==` always have the same hash. Note that `default`, `defaults`,
+    /// `max_depth` and `validate_keys` aren't part of the contents, and don't affect the hash.
+    /// 
+    /// This isn't a cryptographic hash, and shouldn't be used to detect deliberate tampering.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/port", Int(80));
+    /// cmap.add("debug", Bool(false));
+    /// 
+    /// let hash = cmap.content_hash();
+    /// assert_eq!(cmap.clone().content_hash(), hash);
+    /// 
+    /// cmap.add_auto("http/port", Int(8080));
+    /// assert_ne!(cmap.content_hash(), hash);
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        hash::content_hash(self)
+    }
+
+    /// Visits the map using `visitor`, as a `Map` would be visited by `CfgValue::accept`.
+    /// 
+    /// Entries are visited in sorted order by key. See `CfgVisitor` for an example.
+    pub fn accept(&self, visitor: &mut impl CfgVisitor) {
+        visitor::visit_map(self, visitor);
+    }
+
+    /// Checks whether the map is equal to `other`, treating every `List` as an unordered collection.
+    /// 
+    /// Lists are equal if they contain the same elements the same amount of times, in any order. Submaps and
+    /// elements within lists are compared in the same way, up to `max_depth`, after which they're compared normally.
+    /// Use `==` for a comparison where the order within lists matters.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
+    /// 
+    /// let mut a = CfgMap::new();
+    /// a.add("tags", List(vec![Str("x".into()), Str("y".into()), Str("y".into())]));
+    /// 
+    /// let mut b = CfgMap::new();
+    /// b.add("tags", List(vec![Str("y".into()), Str("x".into()), Str("y".into())]));
+    /// 
+    /// assert!(a.eq_unordered(&b));
+    /// assert!(a != b);
+    /// 
+    /// b.add("tags", List(vec![Str("y".into()), Str("x".into()), Str("x".into())]));
+    /// assert!(!a.eq_unordered(&b));
+    /// ```
+    pub fn eq_unordered(&self, other: &CfgMap) -> bool {
+        fn maps_eq(a: &CfgMap, b: &CfgMap, depth: usize) -> bool {
+            a.len() == b.len() && a.iter().all(|(key, x)| {
+                b.internal_map.get(key).map_or(false, |y| values_eq(x, y, depth))
+            })
+        }
+
+        fn values_eq(a: &CfgValue, b: &CfgValue, depth: usize) -> bool {
+            if depth == 0 {
+                return a == b;
+            }
+
+            match (a, b) {
+                (CfgValue::Map(x), CfgValue::Map(y)) => maps_eq(x, y, depth - 1),
+                (CfgValue::List(x), CfgValue::List(y)) => {
+                    if x.len() != y.len() {
+                        return false;
+                    }
+
+                    // Match every element with a distinct, equal element of the other list.
+                    let mut used = vec![false; y.len()];
+
+                    x.iter().all(|elem| {
+                        let found = (0..y.len()).find(|&i| !used[i] && values_eq(elem, &y[i], depth - 1));
+                        found.map(|i| used[i] = true).is_some()
+                    })
+                },
+                _ => a == b,
+            }
+        }
+
+        maps_eq(self, other, self.max_depth)
+    }
+
+    /// Returns the differences needed to turn this map into `other`, sorted by path.
+    /// 
+    /// Submaps present in both are compared recursively, up to `max_depth`. Any other values,
+    /// including lists, are compared as a whole. See `CfgDiff` for more information.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgDiff, CfgValue::*};
+    /// 
+    /// let mut old = CfgMap::new();
+    /// old.add_auto("http/port", Int(80));
+    /// old.add("debug", Bool(true));
+    /// 
+    /// let mut new = CfgMap::new();
+    /// new.add_auto("http/port", Int(80));
+    /// new.add_auto("http/host", Str("localhost".into()));
+    /// 
+    /// assert_eq!(old.diff(&new), vec![
+    ///     CfgDiff::Removed { path: "debug".into(), value: Bool(true) },
+    ///     CfgDiff::Added { path: "http/host".into(), value: Str("localhost".into()) },
+    /// ]);
+    /// ```
+    pub fn diff(&self, other: &CfgMap) -> Vec<CfgDiff> {
+        let mut out = Vec::new();
+        diff::diff_maps(self, other, "", self.max_depth, &mut out);
+
+        out.sort_by(|a, b| a.path().cmp(b.path()));
+        out
+    }
+
+    /// Applies a list of differences, such as ones created using `diff`, onto the map.
     /// 
-    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
-    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// `Added` and `Changed` values are inserted at their path, creating any missing submaps along it,
+    /// and `Removed` values are removed, if present. The old values within the differences aren't checked.
+    /// Use `apply_diff_strict` to ensure the map is in the state the differences expect.
     /// 
-    /// In order to add a default value to a normal submap - you would need to do this manually,
-    /// as this function will always use `get_mut`.
+    /// If an error occurs, the map is left untouched.
     /// 
     /// ## Examples
-    /// 
     /// ```
     /// use cfgmap::{CfgMap, CfgValue::*};
     /// 
-    /// let mut cmap = CfgMap::new();
+    /// let mut old = CfgMap::new();
+    /// old.add_auto("http/port", Int(80));
     /// 
-    /// // Works - a root add like this will always work.
-    /// assert!(cmap.add("k1", Int(5)).is_ok());
+    /// let mut new = CfgMap::new();
+    /// new.add_auto("http/port", Int(8080));
+    /// new.add("debug", Bool(true));
     /// 
-    /// // Doesn't work, because k1 isn't a map.
-    /// assert!(cmap.add("k1/k2", Int(10)).is_err());
+    /// let diff = old.diff(&new);
     /// 
-    /// // Works - returns the old value.
-    /// let r = cmap.add("k1", Float(8.0));
-    /// assert_eq!(Ok(Some(Int(5))), r);
+    /// let mut remote = old.clone();
+    /// remote.apply_diff(&diff).unwrap();
+    /// 
+    /// assert_eq!(remote, new);
     /// ```
     /// 
     /// ## Return values
     /// 
-    /// - `Err` if the path as specified by `key` isn't found. In the case above for example, `get_mut("a")` returns a `None`.
-    /// - `Ok(Some(CfgValue))` if the path as specified by key already contained a value, and was overwritten. In this case, the old value is returned.
-    /// - `Ok(None)` otherwise.
-    pub fn add(&mut self, key: &str, value: CfgValue) -> Result<Option<CfgValue>, ()> {
-        let (path, key) = rsplit_once(key, '/');
-
-        if path.is_none(){
-            Ok(self.internal_map.insert(key.to_string(), value))
-        }
-        else {
-            let subtree = self.get_mut(&path.unwrap());
-
-            if subtree.check_that(Condition::IsMap) {
-                subtree.unwrap().as_map_mut().unwrap().add(&key, value)
-            }
-            else {
-                Err(())
-            }
-        }
+    /// - `Err(CfgError::NotAMap)` if a segment along a path exists, but isn't a `Map`.
+    /// - `Err(CfgError::TooDeep)` if a path has more segments than `max_depth`.
+    /// - `Ok(())` otherwise.
+    pub fn apply_diff(&mut self, diff: &[CfgDiff]) -> Result<(), CfgError> {
+        self.apply_diff_with(diff, false)
     }
 
-    /// Gets a reference to a value from within the configuration.
-    /// 
-    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
-    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
-    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// Applies a list of differences onto the map, checking that the map matches them first.
     /// 
-    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
-    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// This works like `apply_diff`, except that every `Added` path must not exist yet, and the values at every
+    /// `Removed` and `Changed` path must be equal to the old values within the differences.
     /// 
-    /// Returns `None` if the key doesn't exist.
+    /// If an error occurs, the map is left untouched.
     /// 
     /// ## Examples
     /// ```
-    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// use cfgmap::{CfgMap, CfgDiff, CfgError, CfgValue::*};
     /// 
     /// let mut cmap = CfgMap::new();
-    /// let mut submap = CfgMap::new();
+    /// cmap.add("port", Int(80));
     /// 
-    /// submap.add("key", Int(5));
-    ///
-    /// cmap.add("sub", Map(submap));
+    /// let diff = vec![CfgDiff::Changed { path: "port".into(), old: Int(443), new: Int(8080) }];
     /// 
-    /// assert!(cmap.get("sub").check_that(IsMap));
-    /// assert!(cmap.get("sub/key").check_that(IsExactlyInt(5)));
+    /// assert_eq!(cmap.apply_diff_strict(&diff), Err(CfgError::DiffConflict("port".into())));
+    /// assert_eq!(cmap.get("port"), Some(&Int(80)));
     /// ```
-    pub fn get(&self, key: &str) -> Option<&CfgValue> {
-        let (h, t) = split_once(key, '/');
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError::NotFound)` if the value at a `Removed` or `Changed` path doesn't exist.
+    /// - `Err(CfgError::DiffConflict)` if a value doesn't match the differences.
+    /// - `Err(CfgError::NotAMap)` if a segment along a path exists, but isn't a `Map`.
+    /// - `Err(CfgError::TooDeep)` if a path has more segments than `max_depth`.
+    /// - `Ok(())` otherwise.
+    pub fn apply_diff_strict(&mut self, diff: &[CfgDiff]) -> Result<(), CfgError> {
+        self.apply_diff_with(diff, true)
+    }
 
-        if t.is_none() {
-            self.internal_map.get(key)
-        }
-        else {
-            let next = self.internal_map.get(&h);
-
-            if let Some(CfgValue::Map(map)) = next {
-                map.get(&t.unwrap())
-            } else if let Some(CfgValue::List(list)) = next {
-                // Get the next segment of the path, and parse as a list index.
-                let (index,new_t) = split_once(&t.unwrap(), '/');
-                let index = index.parse::<usize>();
-
-                // If it's an invalid usize, then the whole path is invalid.
-                if index.is_err() {
-                    None
-                }
-                else if new_t.is_none() {
-                    list.get(index.unwrap())
-                } else {
-                    list.get(index.unwrap()).and_then(|op| {
-                        op.as_map()
-                    }).and_then(|map| {
-                        map.get(&new_t.unwrap())
-                    })
-                }
-            } else {
-                None
+    fn apply_diff_with(&mut self, diff: &[CfgDiff], strict: bool) -> Result<(), CfgError> {
+        let expect = |map: &CfgMap, path: &str, expected: &CfgValue| match map.get(path) {
+            Some(value) if value == expected => Ok(()),
+            Some(_) => Err(CfgError::DiffConflict(path.into())),
+            None => Err(CfgError::NotFound(path.into())),
+        };
+
+        // Applied onto a copy, so that the map is left untouched on failure.
+        let mut target = self.clone();
+
+        for change in diff {
+            match change {
+                CfgDiff::Added { path, value } => {
+                    if strict && target.contains_key(path) {
+                        return Err(CfgError::DiffConflict(path.clone()));
+                    }
+
+                    target.add_auto(path, value.clone())?;
+                },
+                CfgDiff::Removed { path, value } => {
+                    if strict {
+                        expect(&target, path, value)?;
+                    }
+
+                    target.remove(path);
+                },
+                CfgDiff::Changed { path, old, new } => {
+                    if strict {
+                        expect(&target, path, old)?;
+                    }
+
+                    target.add_auto(path, new.clone())?;
+                },
             }
         }
+
+        // The copy doesn't keep the callbacks, so they're moved over before notifying them.
+        target.callbacks = mem::take(&mut self.callbacks);
+        *self = target;
+
+        diff.iter().for_each(|change| self.notify(change.path()));
+        Ok(())
     }
 
-    /// Gets a mutable reference to a value from within the configuration.
-    /// 
-    /// Returns `None` if the key doesn't exist.
+    /// Returns the maximum nesting depth of the configuration.
     /// 
-    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
-    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
-    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// Every non-empty `Map` or `List` adds a level of depth, so lists do count towards depth.
+    /// For example, an empty map has a depth of `0`, while `{ a: 1 }` has a depth of `1`,
+    /// and both `{ a: { b: 1 } }` and `{ a: [1] }` have a depth of `2`.
     /// 
-    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
-    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// Traversal stops at `max_depth`, so the result is never larger than it.
     /// 
     /// ## Examples
     /// ```
-    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// use cfgmap::{CfgMap, CfgValue::*};
     /// 
     /// let mut cmap = CfgMap::new();
-    /// let mut submap = CfgMap::new();
-    ///
-    /// cmap.add("sub", Map(submap));
+    /// assert_eq!(cmap.depth(), 0);
     /// 
-    /// let mut submap = cmap.get_mut("sub");
-    /// assert!(submap.check_that(IsMap));
+    /// cmap.add("a", Int(1));
+    /// assert_eq!(cmap.depth(), 1);
     /// 
-    /// submap.unwrap().as_map_mut().unwrap().add("key", Int(5));
-    /// assert!(cmap.get_mut("sub/key").check_that(IsExactlyInt(5)));
+    /// cmap.add("b", Map(CfgMap::new()));
+    /// cmap.add("b/c", List(vec![Int(1)]));
+    /// assert_eq!(cmap.depth(), 3);
     /// ```
-    pub fn get_mut(&mut self, key: &str) -> Option<&mut CfgValue> {
-        let (h, t) = split_once(key, '/');
-
-        if t.is_none() {
-            self.internal_map.get_mut(key)
-        }
-        else {
-            let next = self.internal_map.get_mut(&h);
-
-            if let Some(CfgValue::Map(map)) = next {
-                map.get_mut(&t.unwrap())
-            } else if let Some(CfgValue::List(list)) = next {
-                // Get the next segment of the path, and parse as a list index.
-                let (index,new_t) = split_once(&t.unwrap(), '/');
-                let index = index.parse::<usize>();
-
-                // If it's an invalid usize, then the whole path is invalid.
-                if index.is_err() {
-                    None
-                }
-                else if new_t.is_none() {
-                    list.get_mut(index.unwrap())
-                } else {
-                    list.get_mut(index.unwrap()).and_then(|op| {
-                        op.as_map_mut()
-                    }).and_then(|map| {
-                        map.get_mut(&new_t.unwrap())
-                    })
-                }
-            } else {
-                None
+    pub fn depth(&self) -> usize {
+        fn depth_of<'a, I: Iterator<Item = &'a CfgValue>>(values: I, limit: usize) -> usize {
+            if limit == 0 {
+                return 0;
             }
+
+            values.map(|value| match value {
+                CfgValue::Map(map) => depth_of(map.values(), limit - 1) + 1,
+                CfgValue::List(list) => depth_of(list.iter(), limit - 1) + 1,
+                _ => 1,
+            }).max().unwrap_or(0)
         }
+
+        depth_of(self.values(), self.max_depth)
     }
 
-    /// Deletes a key from the map, and returns the value associated with it.
-    /// 
-    /// Returns `None` if the key doesn't exist.
+    /// Returns the total amount of leaves within the configuration.
     /// 
-    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
-    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
-    /// This is for convenience sake, as doing this manually can prove to be verbose.
-    /// 
-    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
-    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// Like `find_all`, this descends into both submaps and lists, up to `max_depth`, so a leaf is
+    /// any value which isn't a `Map` or a `List`.
     /// 
     /// ## Examples
     /// ```
-    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// use cfgmap::{CfgMap, CfgValue::*};
     /// 
     /// let mut cmap = CfgMap::new();
-    ///
-    /// cmap.add("sub", Map(CfgMap::new()));
-    /// cmap.add("sub/int", Int(5));
-    /// 
-    /// let num = cmap.remove("sub/int");
-    /// let nothing = cmap.remove("sub/nothing");
+    /// cmap.add("a", Int(1));
+    /// cmap.add("b", Map(CfgMap::new()));
+    /// cmap.add("b/c", List(vec![Int(1), Int(2)]));
     /// 
-    /// assert!(cmap.get("sub/int").is_none());
-    /// assert!(num.check_that(IsExactlyInt(5)));
-    /// assert!(nothing.is_none());
+    /// assert_eq!(cmap.size(), 3);
     /// ```
-    pub fn remove(&mut self, key: &str) -> Option<CfgValue> {
-        self.remove_entry(key).map(|(_, value)| value)
+    pub fn size(&self) -> usize {
+        fn size_of<'a, I: Iterator<Item = &'a CfgValue>>(values: I, limit: usize) -> usize {
+            if limit == 0 {
+                return 0;
+            }
+
+            values.map(|value| match value {
+                CfgValue::Map(map) => size_of(map.values(), limit - 1),
+                CfgValue::List(list) => size_of(list.iter(), limit - 1),
+                _ => 1,
+            }).sum()
+        }
+
+        size_of(self.values(), self.max_depth)
     }
 
-    /// Deletes a key from the map, and returns the value associated with it, if the value obeys the 
-    /// conditions as passed. Useful for when you want to make sure to avoid deleting another value.
-    /// 
-    /// Returns `None` if the key doesn't exist, or the value associated with the key doesn't obey the condition.
-    /// 
-    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
-    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
-    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// Returns the length in bytes of the longest string within the configuration, or `0` if there are none.
     /// 
-    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
-    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// Like `find_all`, this descends into both submaps and lists, up to `max_depth`.
     /// 
     /// ## Examples
     /// ```
-    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// use cfgmap::{CfgMap, CfgValue::*};
     /// 
     /// let mut cmap = CfgMap::new();
-    ///
-    /// cmap.add("sub", Map(CfgMap::new()));
-    /// cmap.add("sub/int", Int(5));
-    /// 
-    /// let float = cmap.remove_if("sub/int", IsFloat);
-    /// assert!(cmap.get("sub/int").is_some());
-    /// assert!(float.is_none());
+    /// cmap.add("name", Str("cfgmap".into()));
+    /// cmap.add("hosts", List(vec![Str("a.com".into()), Str("example.com".into())]));
     /// 
-    /// let int = cmap.remove_if("sub/int", IsInt);
-    /// assert!(cmap.get("sub/int").is_none());
-    /// assert!(int.check_that(IsExactlyInt(5)));
+    /// assert_eq!(cmap.max_string_len(), 11);
+    /// assert!(cmap.max_string_len() <= 4096);
     /// ```
-    pub fn remove_if(&mut self, key: &str, condition: Condition) -> Option<CfgValue> {
-        if self.get(key).check_that(condition) { self.remove(key) } else { None }
+    pub fn max_string_len(&self) -> usize {
+        self.leaves().into_iter().filter_map(|(_, value)| value.as_str().map(String::len)).max().unwrap_or(0)
     }
 
-    /// Deletes a key from the map, and returns the key and value associated with it.
+    /// Returns the length of the longest list within the configuration, or `0` if there are none.
     /// 
-    /// Returns `None` if the key doesn't exist.
+    /// Like `find_all`, this descends into both submaps and lists, up to `max_depth`, so nested lists are counted as well.
     /// 
-    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
-    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
-    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
     /// 
-    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
-    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("ports", List(vec![Int(80), Int(443)]));
+    /// cmap.add_auto("matrix/rows", List(vec![List(vec![Int(1), Int(2), Int(3)])]));
+    /// 
+    /// assert_eq!(cmap.max_list_len(), 3);
+    /// ```
+    pub fn max_list_len(&self) -> usize {
+        fn max_of<'a, I: Iterator<Item = &'a CfgValue>>(values: I, limit: usize) -> usize {
+            if limit == 0 {
+                return 0;
+            }
+
+            values.map(|value| match value {
+                CfgValue::Map(map) => max_of(map.values(), limit - 1),
+                CfgValue::List(list) => list.len().max(max_of(list.iter(), limit - 1)),
+                _ => 0,
+            }).max().unwrap_or(0)
+        }
+
+        max_of(self.values(), self.max_depth)
+    }
+
+    /// Returns a new map with the same structure, where every leaf is replaced by the output of `f`.
+    /// 
+    /// `f` is called with the full path of each leaf, along with the leaf itself. Like `find_all`, this
+    /// descends into both submaps and lists, up to `max_depth`, so a leaf is any value which isn't a `Map`
+    /// or a `List`. The original map is left untouched.
     /// 
     /// ## Examples
     /// ```
-    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
     /// 
     /// let mut cmap = CfgMap::new();
-    ///
-    /// cmap.add("sub", Map(CfgMap::new()));
-    /// cmap.add("sub/int", Int(5));
+    /// cmap.add("timeout_s", Int(5));
+    /// cmap.add("retries", List(vec![Int(1), Int(2)]));
     /// 
-    /// let (key, num) = cmap.remove_entry("sub/int").unwrap();
-    /// let nothing = cmap.remove("sub/nothing");
+    /// let strings = cmap.map_values(|_, value| Str(format!("{:?}", value)));
     /// 
-    /// assert!(cmap.get("sub/int").is_none());
-    /// assert_eq!(key, "int");
-    /// assert!(num.check_that(IsExactlyInt(5)));
-    /// assert!(nothing.is_none());
+    /// assert!(strings.get("timeout_s").check_that(IsExactlyStr("Int(5)".into())));
+    /// assert!(strings.get("retries").check_that(IsListWith(Box::new(IsStr))));
+    /// assert!(cmap.get("timeout_s").check_that(IsExactlyInt(5)));
     /// ```
-    pub fn remove_entry(&mut self, key: &str) -> Option<(String, CfgValue)> {
-        let (path, key) = rsplit_once(key, '/');
+    pub fn map_values<F: Fn(&str, &CfgValue) -> CfgValue>(&self, f: F) -> CfgMap {
+        let mut out = self.clone();
+        out.for_each_leaf_mut(&mut |path, value| *value = f(path, value));
+        out
+    }
 
-        if path.is_none(){
-            self.internal_map.remove_entry(&key)
+    /// Calls `f` with every leaf within the configuration along with its full path, descending into
+    /// both submaps and lists, up to `max_depth`.
+    fn for_each_leaf_mut(&mut self, f: &mut dyn FnMut(&str, &mut CfgValue)) {
+        fn visit(value: &mut CfgValue, path: String, depth: usize, f: &mut dyn FnMut(&str, &mut CfgValue)) {
+            match value {
+                _ if depth == 0 => {},
+                CfgValue::Map(map) => map.internal_map.iter_mut()
+                    .for_each(|(key, value)| visit(value, format!("{}/{}", path, key), depth - 1, f)),
+                CfgValue::List(list) => list.iter_mut().enumerate()
+                    .for_each(|(index, value)| visit(value, format!("{}/{}", path, index), depth - 1, f)),
+                _ => f(&path, value),
+            }
         }
-        else {
-            let subtree = self.get_mut(&path.unwrap());
 
-            if subtree.check_that(Condition::IsMap) {
-                subtree.unwrap().as_map_mut().unwrap().remove_entry(&key)
-            }
-            else {
-                None
-            }
+        let depth = self.max_depth;
+
+        for (key, value) in self.internal_map.iter_mut() {
+            visit(value, key.clone(), depth, f);
         }
     }
 
-    /// Deletes a key from the map, and returns the key and value associated with it, if the value obeys the 
-    /// conditions as passed. Useful for when you want to make sure to avoid deleting another value.
-    /// 
-    /// Returns `None` if the key doesn't exist, or the value associated with the key doesn't obey the condition.
-    /// 
-    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
-    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
-    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// Returns every leaf within the configuration along with its full path, descending into
+    /// both submaps and lists, up to `max_depth`.
+    fn leaves(&self) -> Vec<(String, &CfgValue)> {
+        let mut out = Vec::new();
+
+        for (key, value) in self.internal_map.iter() {
+            collect_leaves(value, key.clone(), self.max_depth, &mut out);
+        }
+
+        out
+    }
+
+    /// Returns an iterator over every leaf at or within `prefix`, along with its full path.
     /// 
-    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
-    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// Like `find_all`, this descends into both submaps and lists, up to `max_depth`, so a leaf is any value
+    /// which isn't a `Map` or a `List`. If the value at `prefix` is a leaf itself, only it is returned.
+    /// An empty `prefix` returns every leaf within the configuration. The order of iteration is arbitrary.
     /// 
     /// ## Examples
     /// ```
-    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// use cfgmap::{CfgMap, CfgValue::*};
     /// 
     /// let mut cmap = CfgMap::new();
-    ///
-    /// cmap.add("sub", Map(CfgMap::new()));
-    /// cmap.add("sub/int", Int(5));
+    /// cmap.add_auto("features/search/enabled", Bool(true));
+    /// cmap.add_auto("features/search/engines", List(vec![Str("fts".into())]));
+    /// cmap.add_auto("features/export", Bool(false));
+    /// cmap.add("name", Str("cfgmap".into()));
     /// 
-    /// let float = cmap.remove_entry_if("sub/int", IsFloat);
-    /// assert!(cmap.get("sub/int").is_some());
-    /// assert!(float.is_none());
+    /// let mut search: Vec<_> = cmap.iter_prefix("features/search").collect();
+    /// search.sort_by(|(a, _), (b, _)| a.cmp(b));
     /// 
-    /// let (key, int) = cmap.remove_entry_if("sub/int", IsInt).unwrap();
-    /// assert!(cmap.get("sub/int").is_none());
-    /// assert_eq!(key, "int");
-    /// assert!(int.check_that(IsExactlyInt(5)));
+    /// assert_eq!(search, vec![
+    ///     ("features/search/enabled".to_string(), &Bool(true)),
+    ///     ("features/search/engines/0".to_string(), &Str("fts".into())),
+    /// ]);
+    /// 
+    /// assert_eq!(cmap.iter_prefix("features").count(), 3);
+    /// assert_eq!(cmap.iter_prefix("missing").count(), 0);
     /// ```
-    pub fn remove_entry_if(&mut self, key: &str, condition: Condition) -> Option<(String, CfgValue)> {
-        if self.get(key).check_that(condition) { self.remove_entry(key) } else { None }
-    }
+    pub fn iter_prefix(&self, prefix: &str) -> impl Iterator<Item = (String, &CfgValue)> {
+        let prefix = prefix.trim_end_matches('/');
 
+        let leaves = if prefix.is_empty() {
+            self.leaves()
+        } else {
+            let mut out = Vec::new();
 
-    /// Checks whether a certain path exists.
-    /// 
-    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
-    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
-    /// This is for convenience sake, as doing this manually can prove to be verbose.
+            if let Some(value) = self.get(prefix) {
+                let depth = self.max_depth.saturating_sub(path_depth(prefix) - 1);
+                collect_leaves(value, prefix.into(), depth, &mut out);
+            }
+
+            out
+        };
+
+        leaves.into_iter()
+    }
+
+    /// Returns a new map containing only the values at `paths`, with their nesting reconstructed.
     /// 
-    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
-    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// This is useful for passing a subset of a configuration to a subsystem, without cloning
+    /// the whole thing. Paths which don't exist are skipped. Since the nesting is reconstructed using
+    /// submaps, a path which indexes into a list, such as `servers/0`, will result in a submap with the key `0`.
     /// 
     /// ## Examples
     /// ```
-    /// use cfgmap::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
     /// 
     /// let mut cmap = CfgMap::new();
-    /// let mut submap = CfgMap::new();
-    ///
-    /// cmap.add("num", Int(10));
-    /// submap.add("num", Int(20));
-    /// cmap.add("sub", Map(submap));
+    /// cmap.add_auto("http/port", Int(8080));
+    /// cmap.add_auto("http/host", Str("localhost".into()));
+    /// cmap.add_auto("db/url", Str("postgres://".into()));
     /// 
-    /// assert!(cmap.contains_key("num"));
-    /// assert!(cmap.contains_key("sub/num"));
+    /// let http = cmap.select(&["http/port", "db/missing"]);
+    /// 
+    /// assert!(http.get("http/port").check_that(IsExactlyInt(8080)));
+    /// assert!(http.get("http/host").is_none());
+    /// assert!(http.get("db").is_none());
     /// ```
-    pub fn contains_key(&self, key: &str) -> bool {
-        self.get(key).is_some()
+    pub fn select(&self, paths: &[&str]) -> CfgMap {
+        let mut out = CfgMap::new();
+
+        for path in paths {
+            if let Some(value) = self.get(path) {
+                // Selecting both a path and one of its descendants may conflict, in which case the first one is kept.
+                let _ = out.add_auto(path, value.clone());
+            }
+        }
+
+        out
     }
 
-    /// Gets a reference to an option within the configuration.
-    /// 
-    /// It first tries to get 
-    /// `category/option` within the normal values. If this doesn't exist, it will then 
-    /// try to retrieve `option` from the default path instead (`self.default/option`).
-    /// 
-    /// Note that if `default` wasn't set on construction, this function will instead retrieve
-    /// the value from the root directory (`option`) directly.
+    /// Removes every entry within the submap at `key`, while keeping the submap itself.
     /// 
-    /// Returns `None` if the key doesn't exist in either map.
+    /// This differs from `remove`, which deletes the key entirely. It is useful for
+    /// resetting a single section of the configuration, for example before reloading it.
     /// 
     /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
     /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
-    /// This is for convenience sake, as doing this manually can prove to be verbose.
     /// 
-    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
-    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+    /// 
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add("http", Map(CfgMap::new()));
+    /// cmap.add("http/port", Int(8080));
+    /// 
+    /// assert!(cmap.clear_subtree("http").is_ok());
+    /// assert!(cmap.get("http").check_that(IsExactlyMap(CfgMap::new())));
+    /// 
+    /// assert!(cmap.clear_subtree("missing").is_err());
+    /// ```
+    /// 
+    /// ## Return values
+    /// 
+    /// - `Err(CfgError::NotFound)` if `key` doesn't exist.
+    /// - `Err(CfgError::NotAMap)` if the value at `key` isn't a `Map`.
+    /// - `Ok(())` otherwise.
+    pub fn clear_subtree(&mut self, key: &str) -> Result<(), CfgError> {
+        match self.get_mut(key) {
+            Some(CfgValue::Map(map)) => {
+                map.internal_map.clear();
+                Ok(())
+            },
+            Some(_) => Err(CfgError::NotAMap(key.into())),
+            None => Err(CfgError::NotFound(key.into())),
+        }
+    }
+
+    /// Coerces every `Str` within the configuration into its most specific type, using `CfgValue::coerce`.
+    /// 
+    /// This goes through every submap and list recursively, up to `max_depth`, and is useful to normalise
+    /// configurations where every value is a string, such as ones sourced from environment variables.
     /// 
     /// ## Examples
     /// ```
     /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
     /// 
     /// let mut cmap = CfgMap::new();
-    /// let mut submap = CfgMap::new();
     /// 
-    /// submap.add("OP1", Int(5));
-    /// cmap.add("OP1", Int(8));
+    /// cmap.add("port", Str("8080".into()));
+    /// cmap.add("flags", List(vec![Str("false".into()), Str("0.5".into())]));
+    /// cmap.add("host", Str("localhost".into()));
     /// 
-    /// cmap.add("sub", Map(submap));
+    /// cmap.coerce_all();
     /// 
-    /// assert!(cmap.get_option("sub", "OP1").check_that(IsExactlyInt(5)));
-    /// assert!(cmap.get_option("sub", "OP1").check_that(IsExactlyInt(5)));
-    /// assert!(cmap.get_option("sub", "OP2").is_none());
+    /// assert!(cmap.get("port").check_that(IsExactlyInt(8080)));
+    /// assert!(cmap.get("flags").check_that(IsExactlyList(vec![Bool(false), Float(0.5)])));
+    /// assert!(cmap.get("host").check_that(IsExactlyStr("localhost".into())));
     /// ```
-    pub fn get_option(&self, category: &str, option: &str) -> Option<&CfgValue> {
-        let fullkey = format!("{}/{}", category, option);
-        let default = format!("{}{}", self.default, option);
-        self.get(&fullkey).or(self.get(&default))
+    pub fn coerce_all(&mut self) {
+        self.for_each_leaf_mut(&mut |_, value| *value = value.coerce());
     }
 
-    /// Updates the option with the new value `to`.
+    /// Removes leading and trailing whitespace from every `Str`, descending into submaps and lists up to `max_depth`.
     /// 
-    /// It first tries to get 
-    /// `category/option` within the normal values. If this doesn't exist, it will then 
-    /// try to retrieve `option` from the default path instead (`self.default/option`).
+    /// ## Examples
+    /// ```
+    /// use cfgmap::{CfgMap, CfgValue::*};
     /// 
-    /// Note that if `default` wasn't set on construction, this function will instead retrieve
-    /// the value from the root directory (`option`) directly.
+    /// let mut cmap = CfgMap::new();
+    /// cmap.add_auto("http/host", Str(" localhost ".into()));
+    /// cmap.add("tags", List(vec![Str("a\t".into()), Int(5)]));
     /// 
-    /// The `key` can be of the form of the path `"a/b/...y/z/"`, in which case it will
-    /// go through the inner submaps `"a/b/..."` until a submap isn't found, or the end is reached.
-    /// This is for convenience sake, as doing this manually can prove to be verbose.
+    /// cmap.trim_strings();
     /// 
-    /// This key can also index into lists. So, for example `a/0/b` would try checking if `"a"`
-    /// is a list, and index into it. Otherwise it will try to find an internal map with the key `0`.
+    /// assert_eq!(cmap.get("http/host"), Some(&Str("localhost".into())));
+    /// assert_eq!(cmap.get("tags"), Some(&List(vec![Str("a".into()), Int(5)])));
+    /// ```
+    pub fn trim_strings(&mut self) {
+        self.for_each_leaf_mut(&mut |_, value| {
+            if let CfgValue::Str(s) = value {
+                if s.trim().len() != s.len() {
+                    *s = s.trim().to_string();
+                }
+            }
+        });
+    }
+
+    /// Converts every `Float` with no fractional part into an `Int`, descending into submaps and lists up to `max_depth`.
+    /// 
+    /// This is useful for formats such as json, where `2.0` is loaded as a `Float` even if an integer was meant.
+    /// Floats outside the range of `Int` (`i64`), as well as infinite and `NaN` values, are left untouched.
     /// 
     /// ## Examples
     /// ```
     /// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
     /// 
     /// let mut cmap = CfgMap::new();
-    /// let mut submap = CfgMap::new();
-    /// 
-    /// submap.add("OP1", Int(5));
-    /// cmap.add("OP1", Int(8));
-    /// 
-    /// cmap.add("sub", Map(submap));
     /// 
-    /// let ol1 = cmap.update_option("sub", "OP1", Int(10));
-    /// let ol2 = cmap.update_option("foo", "OP1", Int(16));
-    /// let ol3 = cmap.update_option("sub", "OP2", Int(99));
+    /// cmap.add("workers", Float(2.0));
+    /// cmap.add("ratio", Float(0.5));
+    /// cmap.add("sizes", List(vec![Float(-8.0), Float(1e30)]));
     /// 
-    /// assert!(cmap.get_option("sub", "OP1").check_that(IsExactlyInt(10)));
-    /// assert!(cmap.get_option("foo", "OP1").check_that(IsExactlyInt(16)));
-    /// assert!(cmap.get_option("sub", "OP2").is_none());
+    /// cmap.normalize_numbers();
     /// 
-    /// assert_eq!(ol1, Some(Int(5)));
-    /// assert_eq!(ol2, Some(Int(8)));
-    /// assert_eq!(ol3, None);
+    /// assert!(cmap.get("workers").check_that(IsExactlyInt(2)));
+    /// assert!(cmap.get("ratio").check_that(IsExactlyFloat(0.5)));
+    /// assert!(cmap.get("sizes").check_that(IsExactlyList(vec![Int(-8), Float(1e30)])));
     /// ```
-    pub fn update_option(&mut self, category: &str, option: &str, to: CfgValue) -> Option<CfgValue> {
-        let fullkey = format!("{}/{}", category, option);
-        let default = format!("{}{}", self.default, option);
+    pub fn normalize_numbers(&mut self) {
+        self.for_each_leaf_mut(&mut |_, value| {
+            if let (CfgValue::Float(_), Some(i)) = (&*value, value.to_int_checked()) {
+                *value = CfgValue::Int(i);
+            }
+        });
+    }
+
+    /// Returns the full paths of `option` within each of the default paths, in the order they should be tried.
+    fn default_keys(&self, option: &str) -> Vec<String> {
+        let join = |path: &str| {
+            if path.is_empty() || path.ends_with('/') {
+                format!("{}{}", path, option)
+            } else {
+                format!("{}/{}", path, option)
+            }
+        };
 
-        if let Some(x) = self.get_mut(&fullkey) {
-            Some(mem::replace(x, to))
-        } else if let Some(x) = self.get_mut(&default) {
-            Some(mem::replace(x, to))
+        if self.defaults.is_empty() {
+            vec![join(&self.default)]
         } else {
-            None
+            self.defaults.iter().map(|path| join(path)).collect()
         }
     }
 }
 
+impl From<HashMap<String, CfgValue>> for CfgMap {
+    fn from(map: HashMap<String, CfgValue>) -> Self {
+        CfgMap::with_hashmap(map)
+    }
+}
+
 #[cfg(feature = "from_json")]
 impl From<JsonValue> for CfgMap {
     fn from(opt: JsonValue) -> Self {
@@ -1001,12 +4952,6 @@ pub mod prelude {
 
 #[cfg(test)]
 mod tests {
-    #[cfg(feature = "from_json")]
-    use serde_json;
-
-    #[cfg(feature = "from_toml")]
-    use toml;
-
     use crate::prelude::*;
 
     #[cfg(feature = "from_yaml")]
@@ -1120,4 +5065,543 @@ string: \"string\"
         assert!(cmap.get("sub/integer").check_that(IsExactlyInt(20)));
         assert!(cmap.get("array").check_that(IsListWith(Box::new(IsInt)) & IsListWithLength(2)));
     }
+
+    #[test]
+    fn get_many_test() {
+        let mut cmap = CfgMap::new();
+        cmap.add("a", Int(1)).unwrap();
+        cmap.add("b", Map(CfgMap::new())).unwrap();
+        cmap.add("b/c", Str("c".into())).unwrap();
+
+        let values = cmap.get_many(&["a", "b/c", "b/d", "a/b"]);
+
+        assert_eq!(values.len(), 4);
+        assert!(values[0].check_that(IsExactlyInt(1)));
+        assert!(values[1].check_that(IsExactlyStr("c".into())));
+        assert!(values[2].is_none());
+        assert!(values[3].is_none());
+        assert!(cmap.get_many(&[]).is_empty());
+    }
+
+    #[test]
+    fn cascading_defaults_test() {
+        let mut cmap = CfgMap::with_defaults(vec!["env".into(), "global/".into()]);
+        assert_eq!(cmap.defaults, vec!["env/".to_string(), "global/".to_string()]);
+
+        cmap.add("env", Map(CfgMap::new())).unwrap();
+        cmap.add("global", Map(CfgMap::new())).unwrap();
+        cmap.add("global/retries", Int(3)).unwrap();
+
+        assert!(cmap.get_option("net", "retries").check_that(IsExactlyInt(3)));
+
+        let old = cmap.update_option("net", "retries", Int(5));
+        assert_eq!(old, Some(Int(3)));
+        assert!(cmap.get("global/retries").check_that(IsExactlyInt(5)));
+        assert!(cmap.update_option("net", "missing", Int(1)).is_none());
+    }
+
+    #[test]
+    fn into_iter_test() {
+        let mut cmap = CfgMap::new();
+        cmap.add("a", Int(1)).unwrap();
+        cmap.add("b", Map(CfgMap::new())).unwrap();
+        cmap.add("b/c", Int(2)).unwrap();
+
+        for (_, value) in &mut cmap {
+            if let Int(i) = value {
+                *i += 10;
+            }
+        }
+
+        let mut keys: Vec<&String> = (&cmap).into_iter().map(|(k, _)| k).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["a", "b"]);
+
+        let mut owned: Vec<(String, CfgValue)> = cmap.into_iter().collect();
+        owned.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        assert_eq!(owned[0], ("a".into(), Int(11)));
+        assert!(owned[1].1.get("c").check_that(IsExactlyInt(2)));
+    }
+
+    #[test]
+    fn from_iter_test() {
+        let mut cmap: CfgMap = vec![("a".to_string(), Int(1)), ("b".to_string(), Str("b".into()))]
+            .into_iter()
+            .collect();
+
+        assert!(cmap.default.is_empty());
+        assert!(cmap.get("a").check_that(IsExactlyInt(1)));
+
+        cmap.extend(vec![("a".to_string(), Int(2)), ("c".to_string(), Bool(true))]);
+
+        assert_eq!(cmap.len(), 3);
+        assert!(cmap.get("a").check_that(IsExactlyInt(2)));
+        assert!(cmap.get("c").check_that(IsTrue));
+    }
+
+    #[test]
+    fn max_depth_test() {
+        let mut cmap = CfgMap::new();
+        cmap.add("a", Map(CfgMap::new())).unwrap();
+        cmap.add("a/b", Map(CfgMap::new())).unwrap();
+        cmap.add("a/b/c", Str("1".into())).unwrap();
+        cmap.add("d", Str("2".into())).unwrap();
+
+        cmap.max_depth = 2;
+
+        assert!(cmap.get("a/b").is_some());
+        assert!(cmap.get("a/b/c").is_none());
+        assert!(cmap.get_mut("a/b/c").is_none());
+        assert!(cmap.add("a/b/c", Int(1)).is_err());
+
+        cmap.coerce_all();
+
+        assert!(cmap.get("d").check_that(IsExactlyInt(2)));
+        assert!(cmap.get("a/b").and_then(|b| b.get("c")).check_that(IsExactlyStr("1".into())));
+    }
+
+    #[test]
+    fn eq_ignores_settings_test() {
+        let mut a = CfgMap::new();
+        a.add("port", Int(80)).unwrap();
+
+        let mut b = a.clone();
+        b.max_depth = 1;
+        b.validate_keys = true;
+        b.on_change("port", Box::new(|_| {}));
+        assert_eq!(a, b);
+
+        b.default = "port".into();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn merge_test() {
+        let mut base = CfgMap::new();
+        base.add("tags", list![1, 2]).unwrap();
+        base.add("name", Str("base".into())).unwrap();
+        base.add("sub", Map(CfgMap::new())).unwrap();
+        base.add("sub/keep", Int(1)).unwrap();
+
+        let mut other = CfgMap::new();
+        other.add("tags", list![2, 3]).unwrap();
+        other.add("name", Int(5)).unwrap();
+        other.add("sub", Map(CfgMap::new())).unwrap();
+        other.add("sub/new", Int(2)).unwrap();
+
+        let mut replaced = base.clone();
+        replaced.merge(other.clone());
+        assert!(replaced.get("tags").check_that(IsExactlyList(vec![Int(2), Int(3)])));
+        assert!(replaced.get("name").check_that(IsExactlyInt(5)));
+        assert!(replaced.get("sub/keep").check_that(IsExactlyInt(1)));
+        assert!(replaced.get("sub/new").check_that(IsExactlyInt(2)));
+
+        let mut appended = base.clone();
+        appended.merge_with(other.clone(), MergeStrategy::Append);
+        assert!(appended.get("tags").check_that(IsExactlyList(vec![Int(1), Int(2), Int(2), Int(3)])));
+
+        let mut shallow = base.clone();
+        shallow.max_depth = 0;
+        shallow.merge_with(other.clone(), MergeStrategy::Append);
+        shallow.max_depth = crate::DEFAULT_MAX_DEPTH;
+        assert!(shallow.get("tags").check_that(IsExactlyList(vec![Int(1), Int(2), Int(2), Int(3)])));
+        assert!(shallow.get("sub/keep").is_none());
+        assert!(shallow.get("sub/new").check_that(IsExactlyInt(2)));
+
+        let mut unioned = base;
+        unioned.merge_with(other, MergeStrategy::Union);
+        assert!(unioned.get("tags").check_that(IsExactlyList(vec![Int(1), Int(2), Int(3)])));
+    }
+
+    #[test]
+    fn rename_test() {
+        let mut cmap = CfgMap::new();
+        cmap.add("a", Map(CfgMap::new())).unwrap();
+        cmap.add("a/b", Int(1)).unwrap();
+        cmap.add("c", Int(2)).unwrap();
+
+        assert!(cmap.rename("a/b", "x/y/z").is_ok());
+        assert!(cmap.get("a/b").is_none());
+        assert!(cmap.get("x/y").check_that(IsMap));
+        assert!(cmap.get("x/y/z").check_that(IsExactlyInt(1)));
+
+        assert_eq!(cmap.rename("x/y/z", "c/d"), Err(CfgError::NotAMap("c".into())));
+        assert!(cmap.get("x/y/z").check_that(IsExactlyInt(1)));
+
+        assert_eq!(cmap.rename("nothing", "d"), Err(CfgError::NotFound("nothing".into())));
+
+        cmap.add("list", List(vec![Int(1)])).unwrap();
+        assert_eq!(cmap.rename("list/0", "e"), Err(CfgError::NotAMap("list".into())));
+        assert!(cmap.get("list/0").check_that(IsExactlyInt(1)));
+        assert!(cmap.get("e").is_none());
+    }
+
+    #[test]
+    fn find_all_test() {
+        let mut server = CfgMap::new();
+        server.add("host", Str("a".into())).unwrap();
+
+        let mut cmap = CfgMap::new();
+        cmap.add("servers", List(vec![Map(server), Int(5)])).unwrap();
+        cmap.add("retries", Int(3)).unwrap();
+
+        let mut strings = cmap.find_all(IsStr);
+        strings.sort();
+        assert_eq!(strings, vec!["servers/0/host"]);
+        assert!(cmap.get(&strings[0]).check_that(IsExactlyStr("a".into())));
+
+        let mut ints = cmap.find_all(IsInt);
+        ints.sort();
+        assert_eq!(ints, vec!["retries", "servers/1"]);
+
+        assert!(cmap.find_all(IsList | IsMap).is_empty());
+    }
+
+    #[test]
+    fn as_list_type_test() {
+        let ints = list![1, 2, 3];
+        let strs = list!["a", "b"];
+        let mixed = list![1, "b"];
+
+        assert_eq!(ints.as_int_list(), Some(vec![1, 2, 3]));
+        assert_eq!(strs.as_str_list(), Some(vec!["a", "b"]));
+        assert_eq!(list![true].as_bool_list(), Some(vec![true]));
+        assert_eq!(list![0.5].as_float_list(), Some(vec![0.5]));
+        assert_eq!(List(vec![]).as_int_list(), Some(vec![]));
+
+        assert_eq!(ints.as_float_list(), None);
+        assert_eq!(mixed.as_int_list(), None);
+        assert_eq!(mixed.as_str_list(), None);
+        assert_eq!(Int(5).as_int_list(), None);
+    }
+
+    #[test]
+    fn map_values_test() {
+        let mut cmap = CfgMap::new();
+        cmap.add_auto("a/b", Int(1)).unwrap();
+        cmap.add("list", list![2, 3]).unwrap();
+        cmap.add_auto("a/empty", Map(CfgMap::new())).unwrap();
+
+        let paths = cmap.map_values(|path, _| Str(path.into()));
+
+        assert!(paths.get("a/b").check_that(IsExactlyStr("a/b".into())));
+        assert!(paths.get("list").check_that(IsExactlyList(vec![Str("list/0".into()), Str("list/1".into())])));
+        assert!(paths.get("a/empty").check_that(IsExactlyMap(CfgMap::new())));
+    }
+
+    #[test]
+    fn get_glob_test() {
+        let mut cmap = CfgMap::new();
+        cmap.add_auto("a/b/c", Int(1)).unwrap();
+        cmap.add_auto("a/d", Int(2)).unwrap();
+        cmap.add("list", List(vec![Int(3), Int(4)])).unwrap();
+
+        let paths = |pattern| cmap.get_glob(pattern).into_iter().map(|(p, _)| p).collect::<Vec<_>>();
+
+        assert_eq!(paths("a/*"), vec!["a/b", "a/d"]);
+        assert_eq!(paths("list/*"), vec!["list/0", "list/1"]);
+        assert_eq!(paths("**/c"), vec!["a/b/c"]);
+        assert_eq!(paths("a/**/c"), vec!["a/b/c"]);
+        assert_eq!(paths("a/b/**/c"), vec!["a/b/c"]);
+        assert_eq!(paths("a/**"), vec!["a/b", "a/b/c", "a/d"]);
+        assert_eq!(paths("**/**/d"), vec!["a/d"]);
+        assert_eq!(paths("a/b/c"), vec!["a/b/c"]);
+        assert!(paths("x/*").is_empty());
+
+        let mut repeated = CfgMap::new();
+        repeated.add_auto("a/c/c/d", Int(5)).unwrap();
+        let paths = |pattern| repeated.get_glob(pattern).into_iter().map(|(p, _)| p).collect::<Vec<_>>();
+        assert_eq!(paths("**/c/**"), vec!["a/c/c", "a/c/c/d"]);
+    }
+
+    #[test]
+    #[cfg(feature = "from_json")]
+    fn parse_error_test() {
+        let error = CfgMap::from_json_str("{\n  \"a\": x\n}").unwrap_err();
+
+        assert_eq!(error, CfgError::Parse { message: "expected value".into(), line: Some(2), column: Some(8) });
+        assert_eq!(error.to_string(), "parse error at line 2, column 8: expected value");
+
+        let error = CfgMap::from_json_str("[]").unwrap_err();
+        assert_eq!(error.to_string(), "parse error: json value passed wasn't an object");
+    }
+
+    #[test]
+    fn diff_test() {
+        let mut old = CfgMap::new();
+        old.add_auto("a/b", Int(1)).unwrap();
+        old.add_auto("a/c", list![1, 2]).unwrap();
+        old.add_auto("d/e", Int(2)).unwrap();
+
+        let mut new = CfgMap::new();
+        new.add_auto("a/b", Int(1)).unwrap();
+        new.add_auto("a/c", list![1, 3]).unwrap();
+        new.add("d", Int(3)).unwrap();
+        new.add_auto("f/g", Bool(true)).unwrap();
+
+        let diff = old.diff(&new);
+        let paths: Vec<&str> = diff.iter().map(CfgDiff::path).collect();
+
+        assert_eq!(paths, vec!["a/c", "d", "f"]);
+        assert!(new.diff(&new).is_empty());
+
+        let mut target = old.clone();
+        target.apply_diff_strict(&diff).unwrap();
+        assert_eq!(target, new);
+
+        // Applying it a second time conflicts, and leaves the map untouched.
+        assert_eq!(target.apply_diff_strict(&diff), Err(CfgError::DiffConflict("a/c".into())));
+        assert_eq!(target, new);
+
+        target.apply_diff(&diff).unwrap();
+        assert_eq!(target, new);
+    }
+
+    #[test]
+    fn from_ini_test() {
+        let cmap = CfgMap::from_ini_str("# comment\n[a]\nx = 1\n[b]\n[a]\ny = 'two'\n").unwrap();
+
+        assert!(cmap.get("a/x").check_that(IsExactlyInt(1)));
+        assert!(cmap.get("a/y").check_that(IsExactlyStr("two".into())));
+        assert!(cmap.get("b").check_that(IsExactlyMap(CfgMap::new())));
+
+        let error = |input| match CfgMap::from_ini_str(input) {
+            Err(CfgError::Parse { line, .. }) => line,
+            _ => None,
+        };
+
+        assert_eq!(error("[a]\nx = 1\ny"), Some(3));
+        assert_eq!(error("[a"), Some(1));
+        assert_eq!(error("a = 1\n[a]"), Some(2));
+    }
+
+    #[test]
+    #[cfg(feature = "from_json")]
+    fn json_with_default_test() {
+        let mut cmap = CfgMap::with_defaults(vec!["env".into(), "global".into()]);
+        cmap.add_auto("global/port", Int(80)).unwrap();
+
+        let json = cmap.to_json_string_with_default().unwrap();
+        assert_eq!(CfgMap::from_json_str_with_default(&json).unwrap(), cmap);
+
+        // Without opting in, the key is left alone.
+        assert!(CfgMap::from_json_str(&json).unwrap().contains_key("$default"));
+        assert_eq!(CfgMap::new().to_json_string_with_default().unwrap(), "{}");
+
+        let cmap = CfgMap::from_json_str_with_default(r#"{ "$default": 5 }"#).unwrap();
+        assert!(cmap.get("$default").check_that(IsExactlyInt(5)));
+        assert_eq!(cmap.default_path(), "");
+    }
+
+    #[test]
+    fn into_type_test() {
+        let mut submap = CfgMap::new();
+        submap.add("a", Int(1)).unwrap();
+
+        assert_eq!(Map(submap.clone()).into_map(), Ok(submap));
+        assert_eq!(list![1, 2].into_list(), Ok(vec![Int(1), Int(2)]));
+        assert_eq!(Str("a".into()).into_str(), Ok("a".to_string()));
+        assert_eq!(Int(5).into_int(), Ok(5));
+        assert_eq!(Int(5).into_float(), Err(Int(5)));
+        assert_eq!(Bool(true).into_map(), Err(Bool(true)));
+    }
+
+    #[test]
+    fn validate_keys_test() {
+        let mut bad = CfgMap::new();
+        bad.insert("x/y".into(), Int(1));
+
+        let mut cmap = CfgMap::new();
+        assert!(cmap.add("a", Map(bad.clone())).is_ok());
+        assert!(cmap.get("a/x/y").is_none());
+
+        let mut cmap = CfgMap::new();
+        cmap.validate_keys = true;
+
+        assert!(cmap.add("a", Map(bad.clone())).is_err());
+        assert_eq!(cmap.insert_path("a", List(vec![Map(bad.clone())])), Err(CfgError::InvalidKey("a/0/x/y".into())));
+        assert_eq!(cmap.add_auto("b/c", Map(bad)), Err(CfgError::InvalidKey("b/c/x/y".into())));
+        assert!(cmap.is_empty());
+
+        assert!(cmap.add_auto("b/c", Int(1)).is_ok());
+    }
+
+    #[test]
+    fn content_hash_test() {
+        let mut a = CfgMap::new();
+        let mut b = CfgMap::new();
+
+        for i in 0..32 {
+            a.add(&i.to_string(), Int(i)).unwrap();
+            b.add(&(31 - i).to_string(), Int(31 - i)).unwrap();
+        }
+
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        // The hash must stay the same between runs and releases.
+        let mut cmap = CfgMap::new();
+        cmap.add_auto("a/b", list![1, "x", true]).unwrap();
+        cmap.add("c", Float(1.5)).unwrap();
+        assert_eq!(cmap.content_hash(), 2099889719671145671);
+
+        let mut other = CfgMap::new();
+        other.add("c", Str("1".into())).unwrap();
+        assert_ne!(other.content_hash(), CfgMap::new().content_hash());
+
+        let mut zero = CfgMap::new();
+        zero.add("x", Float(0.0)).unwrap();
+        let mut negative_zero = CfgMap::new();
+        negative_zero.add("x", Float(-0.0)).unwrap();
+        assert_eq!(zero, negative_zero);
+        assert_eq!(zero.content_hash(), negative_zero.content_hash());
+
+        let mut nan = CfgMap::new();
+        nan.add("x", Float(f64::NAN)).unwrap();
+        let mut negative_nan = CfgMap::new();
+        negative_nan.add("x", Float(-f64::NAN)).unwrap();
+        assert_eq!(nan.content_hash(), negative_nan.content_hash());
+    }
+
+    #[test]
+    fn on_change_test() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+
+        let mut cmap = CfgMap::with_defaults(vec!["global".into()]);
+        cmap.add_auto("global/port", Int(80)).unwrap();
+        cmap.on_change("global", Box::new(move |_| { counter.fetch_add(1, Ordering::SeqCst); }));
+
+        cmap.update_option("http", "port", Int(8080)).unwrap();
+        cmap.modify("global/port", |_| Int(443)).unwrap();
+        cmap.add("global/host", Str("localhost".into())).unwrap();
+        cmap.add("other", Int(1)).unwrap();
+        assert!(cmap.replace("missing", Int(1)).is_none());
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        cmap.add("global/hosts", List(vec![])).unwrap();
+        cmap.push_to("global/hosts", Str("a".into())).unwrap();
+        cmap.extend_at("global/hosts", vec![Str("b".into())]).unwrap();
+        assert!(cmap.push_to("other", Int(2)).is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+
+        cmap.increment("global/port", 1).unwrap();
+        assert!(cmap.increment("global/hosts", 1).is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 7);
+
+        let mut defaults = CfgMap::new();
+        defaults.add_auto("global/port", Int(1)).unwrap();
+        defaults.add_auto("global/debug", Bool(false)).unwrap();
+        defaults.add("unrelated", Int(1)).unwrap();
+        cmap.fill_defaults(&defaults);
+        cmap.overwrite_existing(&defaults);
+        assert_eq!(calls.load(Ordering::SeqCst), 10);
+
+        cmap.apply_diff(&[CfgDiff::Added { path: "global/tls".into(), value: Bool(true) }]).unwrap();
+        cmap.rename("global/tls", "tls").unwrap();
+        cmap.merge(defaults);
+        assert_eq!(calls.load(Ordering::SeqCst), 13);
+
+        // Clones don't keep the callbacks, but still compare equal.
+        let mut clone = cmap.clone();
+        assert_eq!(clone, cmap);
+
+        clone.add("global/port", Int(1)).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 13);
+    }
+
+    #[test]
+    fn file_test() {
+        let dir = std::env::temp_dir().join(format!("cfgmap-file-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut cmap = CfgMap::new();
+        cmap.add_auto("http/port", Int(8080)).unwrap();
+        cmap.add("ratio", Float(2.0)).unwrap();
+        cmap.add("hosts", List(vec![Str("a".into()), Str("b: c".into())])).unwrap();
+
+        let extensions = [
+            ("json", cfg!(feature = "from_json")),
+            ("TOML", cfg!(feature = "from_toml")),
+            ("yaml", cfg!(feature = "from_yaml")),
+            ("yml", cfg!(feature = "from_yaml")),
+        ];
+
+        for (extension, enabled) in extensions {
+            let path = dir.join(format!("config.{}", extension));
+
+            if !enabled {
+                assert_eq!(cmap.save_to_file(&path), Err(CfgError::UnsupportedFormat(extension.to_lowercase())));
+                continue;
+            }
+
+            cmap.save_to_file(&path).unwrap();
+            assert_eq!(CfgMap::load_from_file(&path), Ok(cmap.clone()), "{}", extension);
+        }
+
+        assert_eq!(cmap.save_to_file(dir.join("config")), Err(CfgError::UnsupportedFormat("".into())));
+        assert!(!dir.join("config").exists());
+
+        #[cfg(feature = "from_json")]
+        assert!(matches!(CfgMap::load_from_file(dir.join("missing.json")), Err(CfgError::Io(_))));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn builder_test() {
+        let path = std::env::temp_dir().join(format!("cfgmap-builder-test-{}.txt", std::process::id()));
+
+        assert_eq!(CfgBuilder::new().from_file(&path).build(), Err(CfgError::UnsupportedFormat("txt".into())));
+        assert_eq!(CfgBuilder::new().build(), Ok(CfgMap::new()));
+
+        std::env::set_var("BUILDER_TEST_HTTP__HOST", "localhost");
+        std::env::set_var("BUILDER_TEST_HTTP__PORT", "80");
+        std::env::set_var("BUILDER_TEST_RATIO", "0.5");
+        std::env::set_var("BUILDER_TEST_BAD____KEY", "1");
+        std::env::set_var("BUILDER_TESTING", "1");
+
+        // Later layers take precedence, while keeping the values they don't set.
+        let cmap = CfgBuilder::new()
+            .with_overrides(&[("http/port", Int(8080)), ("http/tls", Bool(true))])
+            .with_env("BUILDER_TEST")
+            .build()
+            .unwrap();
+
+        let mut expected = CfgMap::new();
+        expected.add_auto("http/host", Str("localhost".into())).unwrap();
+        expected.add_auto("http/port", Int(80)).unwrap();
+        expected.add_auto("http/tls", Bool(true)).unwrap();
+        expected.add("ratio", Float(0.5)).unwrap();
+
+        assert_eq!(cmap, expected);
+    }
+
+    #[test]
+    fn partial_ord_test() {
+        use std::cmp::Ordering;
+
+        assert_eq!(Int(i64::MAX).partial_cmp(&Int(i64::MAX - 1)), Some(Ordering::Greater));
+        assert_eq!(Int(1).partial_cmp(&Float(1.0)), Some(Ordering::Less));
+        assert_eq!(Float(1.0).partial_cmp(&Int(1)), Some(Ordering::Greater));
+        assert_eq!(Int(1).partial_cmp(&Float(1.5)), Some(Ordering::Less));
+        assert_eq!(Float(2.0).partial_cmp(&Int(1)), Some(Ordering::Greater));
+        assert_eq!(Int(i64::MAX).partial_cmp(&Float(i64::MAX as f64)), Some(Ordering::Less));
+        assert_eq!(Int((1 << 53) + 1).partial_cmp(&Float((1u64 << 53) as f64)), Some(Ordering::Greater));
+        assert_eq!(Int(1).cmp_loose(&Float(1.0)), Some(Ordering::Equal));
+        assert_eq!(Int((1 << 53) + 1).cmp_loose(&Float((1u64 << 53) as f64)), Some(Ordering::Greater));
+        assert_eq!(Float(-0.0).partial_cmp(&Float(0.0)), Some(Ordering::Equal));
+        assert_eq!(Float(f64::NAN).partial_cmp(&Float(f64::NAN)), None);
+        assert_eq!(Float(f64::NAN).partial_cmp(&Int(0)), None);
+        assert_eq!(Bool(true).partial_cmp(&Int(1)), None);
+
+        let list = List(vec![Int(1)]);
+        assert_eq!(list.partial_cmp(&list.clone()), Some(Ordering::Equal));
+        assert_eq!(list.partial_cmp(&List(vec![Int(2)])), None);
+        assert_eq!(Map(CfgMap::new()).partial_cmp(&Map(CfgMap::new())), Some(Ordering::Equal));
+    }
 }
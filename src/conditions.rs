@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
 use std::ops::{BitAnd, BitOr, Not};
 
 /// Trait for the `check_that` function, that allows it to run a condition on a struct.
@@ -54,6 +56,10 @@ pub enum Condition {
     IsBool,
 
     IsMap,
+
+    /// Verifies the value to be of the type passed. This is useful when the expected type is only known at runtime.
+    IsType(super::CfgType),
+
     /// A combination of two conditions.
     /// 
     /// If both evaluate to `TRUE`, the result is `TRUE`, otherwise it is `FALSE`.
@@ -85,14 +91,122 @@ pub enum Condition {
     /// Verifies it to be a `Bool`, and checks whether it is true.
     IsTrue,
 
+    /// Verifies it to represent `true`, even if it isn't a `Bool`. The following values are accepted:
+    /// 
+    /// - `Bool(true)`
+    /// - `Int(1)`
+    /// - A `Str` equal to `"true"`, `"yes"`, `"on"` or `"1"`, ignoring case.
+    IsTruthy,
+
+    /// Verifies it to represent `false`, even if it isn't a `Bool`. The following values are accepted:
+    /// 
+    /// - `Bool(false)`
+    /// - `Int(0)`
+    /// - A `Str` equal to `"false"`, `"no"`, `"off"` or `"0"`, ignoring case.
+    /// 
+    /// Any other values satisfy neither `IsTruthy` nor `IsFalsy`.
+    IsFalsy,
+
+    /// Verifies it to be an `Int` which is a multiple of the integer passed.
+    /// 
+    /// This always evaluates to `FALSE` if the integer passed is `0`.
+    IsMultipleOf(super::_Int),
+
+    /// Verifies it to be a number within an epsilon of a target, as `Approx(target, epsilon)`.
+    /// 
+    /// Both `Int`s and `Float`s are accepted. This should be preferred over `IsExactlyFloat`,
+    /// which is subject to floating point inaccuracies.
+    Approx(super::_Float, super::_Float),
+
+    /// Verifies it to be a number greater than the float passed.
+    /// 
+    /// Both `Int`s and `Float`s are accepted, and compared using `to_float`. This also applies to the conditions below.
+    GreaterThan(super::_Float),
+
+    /// Verifies it to be a number less than the float passed.
+    LessThan(super::_Float),
+
+    /// Verifies it to be a number greater than or equal to the float passed.
+    GreaterOrEqual(super::_Float),
+
+    /// Verifies it to be a number less than or equal to the float passed.
+    LessOrEqual(super::_Float),
+
+    /// Verifies it to be a `Str` which starts with the string passed.
+    StartsWith(super::_Str),
+
+    /// Verifies it to be a `Str` which ends with the string passed.
+    EndsWith(super::_Str),
+
+    /// Verifies it to be a `Str` which contains the string passed.
+    Contains(super::_Str),
+
+    /// Verifies it to be a `Str` which only contains ASCII characters. An empty string satisfies this.
+    IsAscii,
+
+    /// Verifies it to be a `Str` which only contains ASCII letters and digits. An empty string satisfies this.
+    IsAsciiAlphanumeric,
+
+    /// Verifies it to be a `Str` which is a valid identifier, matching `[A-Za-z_][A-Za-z0-9_]*`.
+    IsIdentifier,
+
+    /// Verifies it to be a `Float` which is neither `NaN` nor infinite.
+    /// 
+    /// Note that `Int`s don't satisfy this condition. To accept any finite number, use `IsInt | IsFiniteFloat`.
+    IsFiniteFloat,
+
     /// Verifies it to be a `List` and applies the condition to each of its elements.
     IsListWith(Box<Condition>),
 
     /// Verifies it to be a `List`, while also having a specific length.
+    /// 
+    /// Combined with `IsListWith`, this can validate fixed-arity lists, such as an RGB color:
+    /// 
+    /// ```
+    /// # use cfgmap::{CfgValue::*, Condition::*, Checkable};
+    /// let color = List(vec![Int(255), Int(128), Int(0)]);
+    /// assert!(color.check_that(IsListWithLength(3) & IsListWith(Box::new(IsInt))));
+    /// ```
     IsListWithLength(usize),
 
-    #[cfg(feature = "from_json")]
-    /// Verifies the value to be `null`. Only availiable while using `from_json`.
+    /// Verifies it to be a `List`, with a length between the two passed, inclusive, as `IsListLenBetween(min, max)`.
+    IsListLenBetween(usize, usize),
+
+    /// Verifies it to be no longer than the length passed.
+    /// 
+    /// For a `Str`, this is its length in bytes. For a `List` it's the amount of elements, and for a `Map`
+    /// the amount of top-level entries. Any other values have no length, and always satisfy this condition.
+    MaxLength(usize),
+
+    /// Verifies it to be a `List` with no duplicate elements.
+    IsUniqueList,
+
+    /// Verifies it to be a `List` whose elements are in non-decreasing order.
+    /// 
+    /// Elements are compared using the `PartialOrd` implementation of `CfgValue`, so numbers (`Int`s and `Float`s,
+    /// which are compared with each other), strings and bools can be ordered. An `Int` and a `Float` holding the
+    /// same number are treated as equal here. If any two adjacent elements can't be compared, this evaluates to `FALSE`.
+    IsSortedList,
+
+    /// Verifies the value using a custom closure, which returns whether the value satisfies it.
+    /// 
+    /// This allows for checks that aren't covered by the other conditions. The closure is wrapped in an `Arc`
+    /// so that the condition can still be cloned, and must be `Send + Sync` so that conditions can be shared across threads.
+    /// 
+    /// ```
+    /// # use cfgmap::{CfgValue::*, Condition::*, Checkable};
+    /// use std::sync::Arc;
+    /// 
+    /// let is_port = Custom(Arc::new(|value| value.as_int().map_or(false, |port| (1..=65535).contains(port))));
+    /// 
+    /// assert!(Int(8080).check_that(is_port.clone()));
+    /// assert!(!Int(0).check_that(is_port.clone()));
+    /// assert!(Str("8080".into()).check_that(is_port | IsStr));
+    /// ```
+    Custom(Arc<dyn Fn(&super::CfgValue) -> bool + Send + Sync>),
+
+    #[cfg(any(feature = "from_json", feature = "from_yaml"))]
+    /// Verifies the value to be `null`. Only availiable while using `from_json` or `from_yaml`.
     IsNull,
 
     #[cfg(feature = "from_toml")]
@@ -119,6 +233,7 @@ impl Condition {
     }
 
     /// Helper function to generate a `NOT` condition.
+    #[allow(clippy::should_implement_trait)]
     pub fn not(self) -> Condition {
         Condition::Not(Box::new(self))
     }
@@ -147,6 +262,7 @@ impl Condition {
             IsList => input.is_list().into(),
             IsMap => input.is_map().into(),
             IsBool => input.is_bool().into(),
+            IsType(t) => (input.cfg_type() == *t).into(),
             TRUE => TRUE,
             FALSE => FALSE,
 
@@ -180,6 +296,26 @@ impl Condition {
             IsExactlyList(s) => input.as_list().map_or(false, |l| *l == *s).into(),
             IsExactlyMap(s) => input.as_map().map_or(false, |l| *l == *s).into(),
             IsTrue => input.as_bool().map_or(false, |b| *b).into(),
+            IsTruthy => (input.as_bool_lenient() == Some(true)).into(),
+            IsFalsy => (input.as_bool_lenient() == Some(false)).into(),
+            IsMultipleOf(d) => input.as_int().map_or(false, |i| *d != 0 && i.wrapping_rem(*d) == 0).into(),
+            Approx(target, epsilon) => input.to_float().map_or(false, |f| (f - target).abs() <= *epsilon).into(),
+            GreaterThan(bound) => input.to_float().map_or(false, |f| f > *bound).into(),
+            LessThan(bound) => input.to_float().map_or(false, |f| f < *bound).into(),
+            GreaterOrEqual(bound) => input.to_float().map_or(false, |f| f >= *bound).into(),
+            LessOrEqual(bound) => input.to_float().map_or(false, |f| f <= *bound).into(),
+            StartsWith(s) => input.as_str().map_or(false, |st| st.starts_with(s.as_str())).into(),
+            EndsWith(s) => input.as_str().map_or(false, |st| st.ends_with(s.as_str())).into(),
+            Contains(s) => input.as_str().map_or(false, |st| st.contains(s.as_str())).into(),
+            IsAscii => input.as_str().map_or(false, |st| st.is_ascii()).into(),
+            IsAsciiAlphanumeric => input.as_str().map_or(false, |st| st.chars().all(|c| c.is_ascii_alphanumeric())).into(),
+            IsIdentifier => input.as_str().map_or(false, |st| {
+                let mut chars = st.chars();
+
+                chars.next().map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+                    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            }).into(),
+            IsFiniteFloat => input.as_float().map_or(false, |f| f.is_finite()).into(),
 
             // Miscellaneous.
             IsListWith(s) => {
@@ -190,14 +326,32 @@ impl Condition {
                         }
                     }
                     TRUE
-                }).map_or(FALSE, |o| o.into())
+                }).unwrap_or(FALSE)
             },
 
             IsListWithLength(l) => input.as_list().map_or(false, |li| *l == li.len()).into(),
+            IsListLenBetween(min, max) => input.as_list().map_or(false, |li| (*min..=*max).contains(&li.len())).into(),
+
+            MaxLength(l) => match input {
+                super::CfgValue::Str(st) => st.len() <= *l,
+                super::CfgValue::List(li) => li.len() <= *l,
+                super::CfgValue::Map(map) => map.len() <= *l,
+                _ => true,
+            }.into(),
+
+            IsUniqueList => input.as_list().map_or(false, |li| {
+                li.iter().enumerate().all(|(i, elem)| !li[..i].contains(elem))
+            }).into(),
+
+            IsSortedList => input.as_list().map_or(false, |li| {
+                li.windows(2).all(|pair| pair[0].cmp_loose(&pair[1]).map_or(false, |ordering| ordering != Ordering::Greater))
+            }).into(),
+
+            Custom(f) => f(input).into(),
 
             // Feature-dependent.
 
-            #[cfg(feature = "from_json")]
+            #[cfg(any(feature = "from_json", feature = "from_yaml"))]
             IsNull => input.is_null().into(),
 
             #[cfg(feature = "from_toml")]
@@ -216,7 +370,43 @@ impl Condition {
     /// Reasoning behind this is that all other values are either incomplete conditions,
     /// or FALSE.
     pub fn to_bool(&self) -> bool {
-        if let Condition::TRUE = self { true } else { false }
+        matches!(self, Condition::TRUE)
+    }
+}
+
+/// A relation between two numbers, used by `CfgMap::check_relation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// The first number is equal to the second.
+    Eq,
+
+    /// The first number isn't equal to the second.
+    Ne,
+
+    /// The first number is less than the second.
+    Lt,
+
+    /// The first number is less than or equal to the second.
+    Le,
+
+    /// The first number is greater than the second.
+    Gt,
+
+    /// The first number is greater than or equal to the second.
+    Ge,
+}
+
+impl Relation {
+    /// Checks whether the relation holds, given the `ordering` of the first number compared to the second.
+    pub(crate) fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Relation::Eq => ordering == Ordering::Equal,
+            Relation::Ne => ordering != Ordering::Equal,
+            Relation::Lt => ordering == Ordering::Less,
+            Relation::Le => ordering != Ordering::Greater,
+            Relation::Gt => ordering == Ordering::Greater,
+            Relation::Ge => ordering != Ordering::Less,
+        }
     }
 }
 
@@ -261,7 +451,7 @@ impl From<Condition> for bool {
 
 #[cfg(test)]
 mod test {
-    use crate::{CfgMap, CfgValue::*, Condition::*, Checkable};
+    use crate::{CfgMap, CfgType, CfgValue::*, Condition, Condition::*, Checkable};
 
     #[test]
     fn basic_and_exact() {
@@ -286,6 +476,57 @@ mod test {
         assert!(s.check_that(IsStr));
         assert!(s.check_that(IsExactlyStr(String::from("hello"))));
         assert!(!s.check_that(IsExactlyStr(String::from("hella"))));
+        assert!(s.check_that(StartsWith("he".into()) & EndsWith("lo".into()) & Contains("ll".into())));
+        assert!(!s.check_that(StartsWith("lo".into()) | EndsWith("he".into()) | Contains("x".into())));
+        assert!(!i.check_that(StartsWith("5".into())));
+
+        // Verifies truthiness
+        assert!(Bool(true).check_that(IsTruthy));
+        assert!(Int(1).check_that(IsTruthy));
+        assert!(Str("Yes".into()).check_that(IsTruthy));
+        assert!(Str("ON".into()).check_that(IsTruthy & !IsFalsy));
+        assert!(Str("off".into()).check_that(IsFalsy));
+        assert!(Int(0).check_that(IsFalsy));
+        assert!(!i.check_that(IsTruthy | IsFalsy));
+        assert!(!s.check_that(IsTruthy | IsFalsy));
+        assert!(!Float(1.0).check_that(IsTruthy));
+
+        // Verifies character classes
+        assert!(s.check_that(IsAscii & IsAsciiAlphanumeric & IsIdentifier));
+        assert!(Str("_table_2".into()).check_that(IsIdentifier));
+        assert!(!Str("2table".into()).check_that(IsIdentifier));
+        assert!(!Str("".into()).check_that(IsIdentifier));
+        assert!(!Str("a-b".into()).check_that(IsAsciiAlphanumeric | IsIdentifier));
+        assert!(!Str("héllo".into()).check_that(IsAscii));
+        assert!(!i.check_that(IsAscii));
+
+        // Verifies multiples
+        assert!(Int(128).check_that(IsMultipleOf(64)));
+        assert!(!Int(100).check_that(IsMultipleOf(64)));
+        assert!(!Int(0).check_that(IsMultipleOf(0)));
+        assert!(Int(i64::MIN).check_that(IsMultipleOf(-1)));
+        assert!(!Float(128.0).check_that(IsMultipleOf(64)));
+
+        // Verifies approximate numbers
+        assert!(Float(0.1 + 0.2).check_that(Approx(0.3, 1e-9)));
+        assert!(!Float(0.1 + 0.2).check_that(IsExactlyFloat(0.3)));
+        assert!(i.check_that(Approx(5.0, 0.0)));
+        assert!(!f.check_that(Approx(2.5, 0.1)));
+        assert!(!s.check_that(Approx(0.0, 1.0)));
+
+        // Verifies bounds
+        assert!(i.check_that(GreaterThan(4.5) & LessThan(5.5)));
+        assert!(i.check_that(GreaterOrEqual(5.0) & LessOrEqual(5.0)));
+        assert!(!i.check_that(GreaterThan(5.0) | LessThan(5.0)));
+        assert!(f.check_that(GreaterOrEqual(1.0)));
+        assert!(!Float(f64::NAN).check_that(GreaterThan(0.0) | LessOrEqual(0.0)));
+        assert!(!s.check_that(GreaterThan(0.0)));
+
+        // Verifies finite float
+        assert!(f.check_that(IsFiniteFloat));
+        assert!(!Float(f64::NAN).check_that(IsFiniteFloat));
+        assert!(!Float(f64::INFINITY).check_that(IsFiniteFloat));
+        assert!(!i.check_that(IsFiniteFloat));
 
         // Verifies bool
         assert!(b.check_that(IsBool));
@@ -304,17 +545,37 @@ mod test {
         assert!(!m.check_that(IsExactlyMap(map)));
     }
 
+    #[test]
+    fn is_type() {
+        assert!(Int(5).check_that(IsType(CfgType::Int)));
+        assert!(!Int(5).check_that(IsType(CfgType::Float)));
+        assert!(Map(CfgMap::new()).check_that(IsType(CfgType::Map) | IsType(CfgType::List)));
+
+        let expected = [("port", CfgType::Int), ("host", CfgType::Str)];
+        let values = [Int(80), Str("localhost".into())];
+
+        for ((_, t), value) in expected.iter().zip(values.iter()) {
+            assert!(value.check_that(IsType(*t)));
+        }
+    }
+
+    #[test]
+    fn is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Condition>();
+    }
+
     #[test]
     fn combinations() {
-        vec![Int(5), Float(9.0), Str(String::from("foobar"))]
+        [Int(5), Float(9.0), Str(String::from("foobar"))]
             .iter()
             .for_each(|e| assert!(e.check_that(IsInt | IsFloat | IsStr)));
 
-        vec![Int(5), Float(9.0), Str(String::from("foobar"))]
+        [Int(5), Float(9.0), Str(String::from("foobar"))]
             .iter()
             .for_each(|e| assert!(!e.check_that(IsList | IsMap)));
 
-        vec![Int(5), Float(9.0), Str(String::from("foobar"))]
+        [Int(5), Float(9.0), Str(String::from("foobar"))]
             .iter()
             .for_each(|e| assert!(!e.check_that(IsInt & IsFloat)));
     }
@@ -326,6 +587,32 @@ mod test {
         assert!(listexample.check_that(IsListWith(Box::new(IsInt | IsFloat))));
         assert!(listexample.check_that(IsListWithLength(2)));
         assert!(!listexample.check_that(IsListWithLength(3)));
+        assert!(listexample.check_that(IsListLenBetween(1, 2) & IsListLenBetween(2, 3)));
+        assert!(!listexample.check_that(IsListLenBetween(3, 5)));
+        assert!(!Str("ab".into()).check_that(IsListLenBetween(0, 5)));
+
+        assert!(listexample.check_that(MaxLength(2)));
+        assert!(!listexample.check_that(MaxLength(1)));
+        assert!(Str("héllo".into()).check_that(MaxLength(6) & !MaxLength(5)));
+        assert!(Int(1_000_000).check_that(MaxLength(0)));
+
+        assert!(listexample.check_that(IsUniqueList));
+        assert!(!List(vec![Int(5), Str("a".into()), Int(5)]).check_that(IsUniqueList));
+        assert!(!Int(5).check_that(IsUniqueList));
+
+        assert!(List(vec![Int(1), Float(1.5), Int(2), Int(2)]).check_that(IsSortedList));
+        assert!(List(vec![Float(1.0), Int(1), Float(1.0)]).check_that(IsSortedList));
+        assert!(List(vec![Str("a".into()), Str("b".into())]).check_that(IsSortedList));
+        assert!(List(vec![]).check_that(IsSortedList));
+        assert!(!List(vec![Int(3), Int(2)]).check_that(IsSortedList));
+        assert!(!List(vec![Int(1), Str("2".into())]).check_that(IsSortedList));
+        assert!(!Str("abc".into()).check_that(IsSortedList));
+
+        let even = Custom(std::sync::Arc::new(|value| value.as_int().map_or(false, |i| i % 2 == 0)));
+        assert!(Int(4).check_that(even.clone()));
+        assert!(!Int(5).check_that(even.clone()));
+        assert!(List(vec![Int(2), Int(8)]).check_that(IsListWith(Box::new(even.clone()))));
+        assert!(Float(4.0).check_that(!even));
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,89 @@
+use super::{CfgMap, CfgValue};
+
+/// A single difference between two `CfgMap`s, created using `CfgMap::diff`.
+///
+/// Each difference refers to a value by its full path, such as `http/port`. Submaps present in
+/// both maps are compared recursively, while any other values, including lists, are compared as a whole.
+/// A list of differences can be applied onto a map using `CfgMap::apply_diff`.
+///
+/// ## Examples
+/// ```
+/// use cfgmap::{CfgMap, CfgDiff, CfgValue::*};
+///
+/// let mut old = CfgMap::new();
+/// old.add_auto("http/port", Int(80));
+///
+/// let mut new = CfgMap::new();
+/// new.add_auto("http/port", Int(8080));
+///
+/// assert_eq!(old.diff(&new), vec![
+///     CfgDiff::Changed { path: "http/port".into(), old: Int(80), new: Int(8080) },
+/// ]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum CfgDiff {
+    /// A value present only in the new map.
+    Added {
+        /// The path of the value.
+        path: String,
+
+        /// The value that was added.
+        value: CfgValue,
+    },
+
+    /// A value present only in the old map.
+    Removed {
+        /// The path of the value.
+        path: String,
+
+        /// The value that was removed.
+        value: CfgValue,
+    },
+
+    /// A value present in both maps, which differs between them.
+    Changed {
+        /// The path of the value.
+        path: String,
+
+        /// The value within the old map.
+        old: CfgValue,
+
+        /// The value within the new map.
+        new: CfgValue,
+    },
+}
+
+impl CfgDiff {
+    /// Returns the path of the value this difference refers to.
+    pub fn path(&self) -> &str {
+        match self {
+            CfgDiff::Added { path, .. } | CfgDiff::Removed { path, .. } | CfgDiff::Changed { path, .. } => path,
+        }
+    }
+}
+
+/// Collects the differences between `old` and `new` into `out`, unsorted.
+pub(crate) fn diff_maps(old: &CfgMap, new: &CfgMap, prefix: &str, depth: usize, out: &mut Vec<CfgDiff>) {
+    let join = |key: &str| if prefix.is_empty() { key.to_string() } else { format!("{}/{}", prefix, key) };
+
+    for (key, old_value) in old.iter() {
+        let path = join(key);
+
+        match (old_value, new.internal_map.get(key)) {
+            (_, None) => out.push(CfgDiff::Removed { path, value: old_value.clone() }),
+            (CfgValue::Map(old_map), Some(CfgValue::Map(new_map))) if depth > 1 => {
+                diff_maps(old_map, new_map, &path, depth - 1, out);
+            },
+            (_, Some(new_value)) if old_value != new_value => {
+                out.push(CfgDiff::Changed { path, old: old_value.clone(), new: new_value.clone() });
+            },
+            _ => {},
+        }
+    }
+
+    for (key, new_value) in new.iter() {
+        if !old.internal_map.contains_key(key) {
+            out.push(CfgDiff::Added { path: join(key), value: new_value.clone() });
+        }
+    }
+}
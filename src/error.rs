@@ -0,0 +1,116 @@
+use super::CfgType;
+use std::error::Error;
+use std::fmt;
+
+/// An error that can occur while working with a `CfgMap`.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum CfgError {
+    /// The value at the given path doesn't exist.
+    NotFound(String),
+
+    /// The value at the given path was expected to be a `Map`, but isn't.
+    NotAMap(String),
+
+    /// The value at the given path was expected to be of a certain type, but isn't.
+    TypeMismatch {
+        /// The path of the value.
+        path: String,
+
+        /// The type that was expected.
+        expected: CfgType,
+
+        /// The type that was actually found.
+        found: CfgType,
+    },
+
+    /// The given path has more segments than the map's `max_depth`.
+    TooDeep(String),
+
+    /// A required option wasn't found within its category, nor within any of the default paths.
+    MissingOption {
+        /// The path that was tried within the category, such as `sub/option`.
+        path: String,
+
+        /// The paths that were tried within the defaults, in order.
+        defaults: Vec<String>,
+    },
+
+    /// The key at the given path contains a `/`, so it would be unreachable using `get`.
+    InvalidKey(String),
+
+    /// The value at the given path doesn't match what a `CfgDiff` expected it to be.
+    DiffConflict(String),
+
+    /// The input couldn't be parsed into a `CfgMap`.
+    Parse {
+        /// The parser's message, without the position.
+        message: String,
+
+        /// The line at which the error occurred, starting from 1, if known.
+        line: Option<usize>,
+
+        /// The column at which the error occurred, starting from 1, if known.
+        column: Option<usize>,
+    },
+
+    /// The map couldn't be serialized into the requested format. Contains the serializer's message.
+    Serialize(String),
+
+    /// An I/O error occurred while reading or writing. Contains the error's message.
+    Io(String),
+
+    /// The file's extension doesn't correspond to a supported format, or the format's feature isn't enabled.
+    /// Contains the file's extension, if any.
+    UnsupportedFormat(String),
+
+    /// An arithmetic operation on the `Int` at the given path would overflow.
+    Overflow(String),
+}
+
+impl fmt::Display for CfgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CfgError::NotFound(path) => write!(f, "no value found at `{}`", path),
+            CfgError::NotAMap(path) => write!(f, "value at `{}` is not a map", path),
+            CfgError::TypeMismatch { path, expected, found } => {
+                write!(f, "expected {} at `{}`, found {}", expected.name(), path, found.name())
+            },
+            CfgError::TooDeep(path) => write!(f, "path `{}` exceeds the maximum depth", path),
+            CfgError::MissingOption { path, defaults } => {
+                let defaults: Vec<String> = defaults.iter().map(|path| format!("`{}`", path)).collect();
+                write!(f, "no option found at `{}` or at default {}", path, defaults.join(", "))
+            },
+            CfgError::InvalidKey(path) => write!(f, "key `{}` contains a `/`", path),
+            CfgError::DiffConflict(path) => write!(f, "value at `{}` doesn't match the diff", path),
+            CfgError::Parse { message, line: Some(line), column: Some(column) } => {
+                write!(f, "parse error at line {}, column {}: {}", line, column, message)
+            },
+            CfgError::Parse { message, .. } => write!(f, "parse error: {}", message),
+            CfgError::Serialize(message) => write!(f, "serialization error: {}", message),
+            CfgError::Io(message) => write!(f, "i/o error: {}", message),
+            CfgError::UnsupportedFormat(extension) => write!(f, "unsupported file format `{}`", extension),
+            CfgError::Overflow(path) => write!(f, "value at `{}` would overflow", path),
+        }
+    }
+}
+
+impl From<std::io::Error> for CfgError {
+    fn from(e: std::io::Error) -> Self {
+        CfgError::Io(e.to_string())
+    }
+}
+
+impl Error for CfgError {}
+
+impl CfgError {
+    /// Creates a `Parse` error at the given position, stripping the position that
+    /// serde_json, toml and yaml-rust all append to their messages.
+    #[cfg(any(feature = "from_json", feature = "from_toml", feature = "from_yaml"))]
+    pub(crate) fn parse_at(message: String, line: usize, column: usize) -> CfgError {
+        let suffix = format!(" at line {} column {}", line, column);
+        let message = message.strip_suffix(&suffix).unwrap_or(&message).to_string();
+
+        CfgError::Parse { message, line: Some(line), column: Some(column) }
+    }
+}
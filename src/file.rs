@@ -0,0 +1,58 @@
+use super::CfgMap;
+use super::CfgError;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "from_yaml")]
+use super::from_yaml;
+
+/// Returns the lowercased extension of `path`, or an empty string if it has none.
+fn extension_of(path: &Path) -> String {
+    path.extension().and_then(OsStr::to_str).unwrap_or("").to_ascii_lowercase()
+}
+
+/// Reads and parses the file at `path`, picking the format by its extension.
+///
+/// The extension is checked before reading, so unsupported files are never opened.
+pub(crate) fn load(path: &Path) -> Result<CfgMap, CfgError> {
+    let extension = extension_of(path);
+
+    match extension.as_str() {
+        #[cfg(feature = "from_json")]
+        "json" => CfgMap::from_json_str(&fs::read_to_string(path)?),
+
+        #[cfg(feature = "from_toml")]
+        "toml" => CfgMap::from_toml_str(&fs::read_to_string(path)?),
+
+        #[cfg(feature = "from_yaml")]
+        "yaml" | "yml" => from_yaml::yaml_str_to_cfg(&fs::read_to_string(path)?),
+
+        _ => Err(CfgError::UnsupportedFormat(extension)),
+    }
+}
+
+/// Serializes `map` into the format corresponding to `extension`.
+#[cfg_attr(not(any(feature = "from_json", feature = "from_toml", feature = "from_yaml")), allow(unused_variables))]
+fn serialize(map: &CfgMap, extension: String) -> Result<String, CfgError> {
+    match extension.as_str() {
+        #[cfg(feature = "from_json")]
+        "json" => map.to_json_string_pretty().map(|json| json + "\n"),
+
+        #[cfg(feature = "from_toml")]
+        "toml" => map.to_toml_string(),
+
+        #[cfg(feature = "from_yaml")]
+        "yaml" | "yml" => from_yaml::cfg_to_yaml_string(map),
+
+        _ => Err(CfgError::UnsupportedFormat(extension)),
+    }
+}
+
+/// Serializes `map` and writes it to the file at `path`, picking the format by its extension.
+///
+/// The map is serialized before the file is opened, so a serialization error leaves any existing file untouched.
+pub(crate) fn save(map: &CfgMap, path: &Path) -> Result<(), CfgError> {
+    let contents = serialize(map, extension_of(path))?;
+    Ok(fs::write(path, contents)?)
+}
@@ -0,0 +1,63 @@
+use super::CfgMap;
+use super::CfgValue;
+use super::CfgError;
+
+fn parse_error(message: &str, line: usize) -> CfgError {
+    CfgError::Parse { message: message.into(), line: Some(line), column: Some(1) }
+}
+
+/// Parses an ini value, keeping quoted values as strings.
+fn parse_value(value: &str) -> CfgValue {
+    let quoted = value.len() >= 2 && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\'')));
+
+    if quoted {
+        CfgValue::Str(value[1..value.len() - 1].into())
+    } else {
+        CfgValue::Str(value.into()).coerce()
+    }
+}
+
+/// Parses an ini string, placing each section within a submap.
+pub(crate) fn ini_to_cfg(input: &str) -> Result<CfgMap, CfgError> {
+    let mut map = CfgMap::new();
+    let mut section: Option<String> = None;
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = match name.strip_suffix(']') {
+                Some(name) => name.trim().to_string(),
+                None => return Err(parse_error("unterminated section header", i + 1)),
+            };
+
+            // Duplicate sections are merged into the existing submap.
+            let entry = map.internal_map.entry(name.clone()).or_insert_with(|| CfgValue::Map(CfgMap::new()));
+
+            if !entry.is_map() {
+                return Err(parse_error(&format!("section `{}` conflicts with an existing key", name), i + 1));
+            }
+
+            section = Some(name);
+            continue;
+        }
+
+        let (key, value) = match line.find('=') {
+            Some(index) => (line[..index].trim(), line[index + 1..].trim()),
+            None => return Err(parse_error("expected `key = value`", i + 1)),
+        };
+
+        let target = match &section {
+            Some(name) => map.internal_map.get_mut(name).and_then(|value| value.as_map_mut()).unwrap(),
+            None => &mut map,
+        };
+
+        target.internal_map.insert(key.into(), parse_value(value));
+    }
+
+    Ok(map)
+}
@@ -1,6 +1,7 @@
 use super::CfgMap;
 use super::CfgValue;
-use serde_json::{Value, Map};
+use super::CfgError;
+use serde_json::{Value, Map, Number};
 
 fn jsonval_to_cfgval(value: Value) -> CfgValue {
     match value {
@@ -15,7 +16,7 @@ fn jsonval_to_cfgval(value: Value) -> CfgValue {
         },
         Value::String(x) => CfgValue::Str(x),
         Value::Array(x) => {
-            CfgValue::List(x.into_iter().map(|v| jsonval_to_cfgval(v)).collect())
+            CfgValue::List(x.into_iter().map(jsonval_to_cfgval).collect())
         },
         Value::Object(x) => jsonmap_to_cfgval(x)
     }
@@ -34,4 +35,107 @@ pub(crate) fn json_to_cfg(value: Value) -> CfgMap {
     } else {
         panic!("Json value passed wasn't Object.")
     }
-}
\ No newline at end of file
+}
+
+/// Returns an error rather than panicking if the value isn't a json `Map`.
+pub(crate) fn try_json_to_cfg(value: Value) -> Result<CfgMap, CfgError> {
+    if value.is_object() {
+        Ok(json_to_cfg(value))
+    } else {
+        Err(CfgError::Parse { message: "json value passed wasn't an object".into(), line: None, column: None })
+    }
+}
+
+/// Applies a json merge patch onto the map, following RFC 7386.
+/// 
+/// `null` values remove the key, objects are merged recursively and anything else replaces the existing value.
+pub(crate) fn merge_patch(map: &mut CfgMap, patch: Map<String, Value>) {
+    for (key, value) in patch {
+        match value {
+            Value::Null => {
+                map.internal_map.remove(&key);
+            },
+            Value::Object(inner) => {
+                let target = map.internal_map.entry(key).or_insert_with(|| CfgValue::Map(CfgMap::new()));
+
+                if !target.is_map() {
+                    *target = CfgValue::Map(CfgMap::new());
+                }
+
+                merge_patch(target.as_map_mut().unwrap(), inner);
+            },
+            value => {
+                map.internal_map.insert(key, jsonval_to_cfgval(value));
+            },
+        }
+    }
+}
+
+/// The key under which the default paths of a map are stored.
+const DEFAULT_KEY: &str = "$default";
+
+/// Converts the map into a json `Value`, storing its default paths under `DEFAULT_KEY`.
+pub(crate) fn cfg_to_json_with_default(map: &CfgMap) -> Value {
+    let mut value = cfg_to_json(map);
+    let object = value.as_object_mut().unwrap();
+
+    if !map.defaults.is_empty() {
+        object.insert(DEFAULT_KEY.into(), map.defaults.iter().cloned().map(Value::String).collect());
+    } else if !map.default.is_empty() {
+        object.insert(DEFAULT_KEY.into(), Value::String(map.default.clone()));
+    }
+
+    value
+}
+
+/// Removes `DEFAULT_KEY` from the map, and restores the default paths stored within it.
+pub(crate) fn take_default(map: &mut CfgMap) {
+    match map.internal_map.remove(DEFAULT_KEY) {
+        Some(CfgValue::Str(default)) => map.set_default(&default),
+        Some(CfgValue::List(list)) if list.iter().all(CfgValue::is_str) => {
+            map.defaults = list.iter().filter_map(CfgValue::as_str).map(|x| super::normalize_default(x.clone())).collect();
+        },
+        Some(other) => {
+            map.internal_map.insert(DEFAULT_KEY.into(), other);
+        },
+        None => {},
+    }
+}
+
+impl From<serde_json::Error> for CfgError {
+    fn from(e: serde_json::Error) -> Self {
+        if e.is_io() {
+            CfgError::Io(e.to_string())
+        } else if e.line() == 0 {
+            // serde_json reports a line of 0 when the error has no position.
+            CfgError::Parse { message: e.to_string(), line: None, column: None }
+        } else {
+            CfgError::parse_at(e.to_string(), e.line(), e.column())
+        }
+    }
+}
+
+fn cfgval_to_jsonval(value: &CfgValue) -> Value {
+    match value {
+        CfgValue::Int(x) => Value::Number((*x).into()),
+        CfgValue::Float(x) => Number::from_f64(*x).map_or(Value::Null, Value::Number),
+        CfgValue::Str(x) => Value::String(x.clone()),
+        CfgValue::Bool(x) => Value::Bool(*x),
+        CfgValue::Map(x) => cfg_to_json(x),
+        CfgValue::List(x) => Value::Array(x.iter().map(cfgval_to_jsonval).collect()),
+        CfgValue::Null => Value::Null,
+
+        #[cfg(feature = "from_toml")]
+        CfgValue::Datetime(x) => Value::String(x.to_string()),
+
+        #[cfg(feature = "from_yaml")]
+        CfgValue::BadValue | CfgValue::Alias(_) => Value::Null,
+    }
+}
+
+/// Converts the map into a json `Object`.
+/// 
+/// Non-finite floats and yaml-specific values become `null`, and `Datetime`s become strings.
+pub(crate) fn cfg_to_json(map: &CfgMap) -> Value {
+    Value::Object(map.iter().map(|(k, v)| (k.clone(), cfgval_to_jsonval(v))).collect())
+}
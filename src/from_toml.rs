@@ -1,5 +1,6 @@
 use super::CfgMap;
 use super::CfgValue;
+use super::CfgError;
 use toml::{value::Value, value::Table};
 
 fn tomlval_to_cfgval(value: Value) -> CfgValue {
@@ -9,7 +10,7 @@ fn tomlval_to_cfgval(value: Value) -> CfgValue {
         Value::Float(x) => CfgValue::Float(x),
         Value::Boolean(x) => CfgValue::Bool(x),
         Value::Array(x) => {
-            CfgValue::List(x.into_iter().map(|v| tomlval_to_cfgval(v)).collect())
+            CfgValue::List(x.into_iter().map(tomlval_to_cfgval).collect())
         },
         Value::Table(x) => tomlmap_to_cfgval(x),
         Value::Datetime(x) => CfgValue::Datetime(x),
@@ -29,4 +30,43 @@ pub(crate) fn toml_to_cfg(value: Value) -> CfgMap {
     } else {
         panic!("Toml value passed wasn't a Table.")
     }
-}
\ No newline at end of file
+}
+
+/// Returns `None` for values which can't be represented in toml.
+fn cfgval_to_tomlval(value: &CfgValue) -> Option<Value> {
+    match value {
+        CfgValue::Int(x) => Some(Value::Integer(*x)),
+        CfgValue::Float(x) => Some(Value::Float(*x)),
+        CfgValue::Str(x) => Some(Value::String(x.clone())),
+        CfgValue::Bool(x) => Some(Value::Boolean(*x)),
+        CfgValue::Map(x) => Some(cfg_to_toml(x)),
+        CfgValue::List(x) => Some(Value::Array(x.iter().filter_map(cfgval_to_tomlval).collect())),
+        CfgValue::Datetime(x) => Some(Value::Datetime(x.clone())),
+
+        #[cfg(any(feature = "from_json", feature = "from_yaml"))]
+        CfgValue::Null => None,
+
+        #[cfg(feature = "from_yaml")]
+        CfgValue::BadValue | CfgValue::Alias(_) => None,
+    }
+}
+
+/// Converts the map into a toml `Table`, skipping any values which can't be represented in toml.
+pub(crate) fn cfg_to_toml(map: &CfgMap) -> Value {
+    Value::Table(map.iter().filter_map(|(k, v)| cfgval_to_tomlval(v).map(|v| (k.clone(), v))).collect())
+}
+
+/// Serializes the map into a toml string.
+pub(crate) fn cfg_to_toml_string(map: &CfgMap) -> Result<String, CfgError> {
+    toml::to_string(&cfg_to_toml(map)).map_err(|e| CfgError::Serialize(e.to_string()))
+}
+
+impl From<toml::de::Error> for CfgError {
+    fn from(e: toml::de::Error) -> Self {
+        match e.line_col() {
+            // toml's positions start from 0, unlike its message.
+            Some((line, column)) => CfgError::parse_at(e.to_string(), line + 1, column + 1),
+            None => CfgError::Parse { message: e.to_string(), line: None, column: None },
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use super::CfgMap;
 use super::CfgValue;
+use super::CfgError;
 use yaml_rust::Yaml as Value;
 use yaml_rust::yaml::Hash;
+use yaml_rust::{ScanError, YamlEmitter, YamlLoader};
 
 fn yamlval_to_cfgval(value: Value) -> CfgValue {
     match value {
@@ -10,7 +12,7 @@ fn yamlval_to_cfgval(value: Value) -> CfgValue {
         Value::Real(x) => CfgValue::Float(x.parse().unwrap()),
         Value::Boolean(x) => CfgValue::Bool(x),
         Value::Array(x) => {
-            CfgValue::List(x.into_iter().map(|v| yamlval_to_cfgval(v)).collect())
+            CfgValue::List(x.into_iter().map(yamlval_to_cfgval).collect())
         },
         Value::Hash(x) => yamlmap_to_cfgval(x),
         Value::Null => CfgValue::Null,
@@ -32,4 +34,64 @@ pub(crate) fn yaml_to_cfg(value: Value) -> CfgMap {
     } else {
         panic!("Yaml value passed wasn't a Hash.")
     }
-}
\ No newline at end of file
+}
+
+/// Parses a yaml string, using its first document. An empty input results in an empty map.
+pub(crate) fn yaml_str_to_cfg(input: &str) -> Result<CfgMap, CfgError> {
+    match YamlLoader::load_from_str(input)?.into_iter().next() {
+        Some(doc @ Value::Hash(_)) => Ok(yaml_to_cfg(doc)),
+        Some(_) => Err(CfgError::Parse { message: "yaml document wasn't a hash".into(), line: None, column: None }),
+        None => Ok(CfgMap::new()),
+    }
+}
+
+fn cfgval_to_yamlval(value: &CfgValue) -> Value {
+    match value {
+        CfgValue::Int(x) => Value::Integer(*x),
+        CfgValue::Float(x) if x.is_nan() => Value::Real(".nan".into()),
+        CfgValue::Float(x) if x.is_infinite() => Value::Real(if *x > 0.0 { ".inf" } else { "-.inf" }.into()),
+        // `Debug` always includes a decimal point, so the value is read back as a float.
+        CfgValue::Float(x) => Value::Real(format!("{:?}", x)),
+        CfgValue::Str(x) => Value::String(x.clone()),
+        CfgValue::Bool(x) => Value::Boolean(*x),
+        CfgValue::Map(x) => cfg_to_yaml(x),
+        CfgValue::List(x) => Value::Array(x.iter().map(cfgval_to_yamlval).collect()),
+
+        #[cfg(feature = "from_toml")]
+        CfgValue::Datetime(x) => Value::String(x.to_string()),
+
+        CfgValue::Null | CfgValue::BadValue | CfgValue::Alias(_) => Value::Null,
+    }
+}
+
+/// Converts the map into a yaml `Hash`, with its keys sorted.
+fn cfg_to_yaml(map: &CfgMap) -> Value {
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+
+    let mut hash = Hash::new();
+
+    for key in keys {
+        hash.insert(Value::String(key.clone()), cfgval_to_yamlval(&map.internal_map[key]));
+    }
+
+    Value::Hash(hash)
+}
+
+/// Serializes the map into a yaml string.
+pub(crate) fn cfg_to_yaml_string(map: &CfgMap) -> Result<String, CfgError> {
+    let mut out = String::new();
+
+    YamlEmitter::new(&mut out).dump(&cfg_to_yaml(map)).map_err(|e| CfgError::Serialize(format!("{:?}", e)))?;
+    out.push('\n');
+
+    Ok(out)
+}
+
+impl From<ScanError> for CfgError {
+    fn from(e: ScanError) -> Self {
+        // yaml's lines start from 1, but its columns start from 0, unlike its message.
+        let (line, column) = (e.marker().line(), e.marker().col() + 1);
+        CfgError::parse_at(e.to_string(), line, column)
+    }
+}
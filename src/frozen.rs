@@ -0,0 +1,64 @@
+use super::CfgMap;
+use std::ops::Deref;
+
+/// An immutable `CfgMap`, created using `CfgMap::freeze`.
+/// 
+/// This only exposes the methods of `CfgMap` which take `&self`, such as `get`, `get_option` or
+/// `contains_key`, so any accidental mutation after the configuration is loaded becomes a compile-time error.
+/// It is also `Send` and `Sync`, so it can be shared freely across threads, for example using an `Arc`.
+/// 
+/// ## Examples
+/// ```
+/// use cfgmap::{CfgMap, CfgValue::*, Checkable, Condition::*};
+/// 
+/// let mut cmap = CfgMap::new();
+/// cmap.add("port", Int(8080));
+/// 
+/// let frozen = cmap.freeze();
+/// 
+/// assert!(frozen.get("port").check_that(IsExactlyInt(8080)));
+/// assert!(frozen.contains_key("port"));
+/// ```
+/// 
+/// Mutating it doesn't compile:
+/// 
+/// ```compile_fail
+/// use cfgmap::{CfgMap, CfgValue::*};
+/// 
+/// let mut frozen = CfgMap::new().freeze();
+/// frozen.add("port", Int(8080));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrozenCfgMap(CfgMap);
+
+impl FrozenCfgMap {
+    /// Consumes the frozen map, returning the inner `CfgMap` which can be mutated again.
+    pub fn thaw(self) -> CfgMap {
+        self.0
+    }
+}
+
+impl Deref for FrozenCfgMap {
+    type Target = CfgMap;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<CfgMap> for FrozenCfgMap {
+    fn from(map: CfgMap) -> Self {
+        FrozenCfgMap(map)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FrozenCfgMap;
+
+    #[test]
+    fn is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FrozenCfgMap>();
+    }
+}
@@ -0,0 +1,96 @@
+use super::{CfgMap, CfgValue};
+
+/// A 64-bit FNV-1a hasher.
+///
+/// This is used instead of `std`'s `DefaultHasher`, whose algorithm isn't guaranteed to stay the same between releases.
+struct Fnv(u64);
+
+impl Fnv {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Fnv::PRIME);
+        }
+    }
+
+    fn write_u64(&mut self, x: u64) {
+        self.write(&x.to_le_bytes());
+    }
+
+    /// Writes the length first, so that adjacent strings can't be confused with each other.
+    fn write_str(&mut self, s: &str) {
+        self.write_u64(s.len() as u64);
+        self.write(s.as_bytes());
+    }
+}
+
+fn hash_map(map: &CfgMap, hasher: &mut Fnv) {
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+
+    hasher.write_u64(keys.len() as u64);
+
+    for key in keys {
+        hasher.write_str(key);
+        hash_value(&map.internal_map[key], hasher);
+    }
+}
+
+fn hash_value(value: &CfgValue, hasher: &mut Fnv) {
+    // Each variant is tagged, so that for example `Int(0)` and `Bool(false)` hash differently.
+    match value {
+        CfgValue::Int(x) => {
+            hasher.write(&[0]);
+            hasher.write(&x.to_le_bytes());
+        },
+        CfgValue::Float(x) => {
+            // `-0.0 == 0.0`, so both must hash the same. Every `NaN` is hashed the same too, to keep the hash stable.
+            let x = if *x == 0.0 { 0.0 } else if x.is_nan() { f64::NAN } else { *x };
+
+            hasher.write(&[1]);
+            hasher.write_u64(x.to_bits());
+        },
+        CfgValue::Str(x) => {
+            hasher.write(&[2]);
+            hasher.write_str(x);
+        },
+        CfgValue::Bool(x) => hasher.write(&[3, *x as u8]),
+        CfgValue::Map(x) => {
+            hasher.write(&[4]);
+            hash_map(x, hasher);
+        },
+        CfgValue::List(x) => {
+            hasher.write(&[5]);
+            hasher.write_u64(x.len() as u64);
+            x.iter().for_each(|elem| hash_value(elem, hasher));
+        },
+
+        #[cfg(feature = "from_toml")]
+        CfgValue::Datetime(x) => {
+            hasher.write(&[6]);
+            hasher.write_str(&x.to_string());
+        },
+
+        #[cfg(any(feature = "from_json", feature = "from_yaml"))]
+        CfgValue::Null => hasher.write(&[7]),
+
+        #[cfg(feature = "from_yaml")]
+        CfgValue::BadValue => hasher.write(&[8]),
+
+        #[cfg(feature = "from_yaml")]
+        CfgValue::Alias(x) => {
+            hasher.write(&[9]);
+            hasher.write_u64(*x as u64);
+        },
+    }
+}
+
+/// Hashes the contents of the map, visiting keys in sorted order.
+pub(crate) fn content_hash(map: &CfgMap) -> u64 {
+    let mut hasher = Fnv(Fnv::OFFSET_BASIS);
+    hash_map(map, &mut hasher);
+    hasher.0
+}
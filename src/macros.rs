@@ -142,4 +142,36 @@ macro_rules! as_mut_type {
             }
         }
     };
-}
\ No newline at end of file
+}
+
+macro_rules! into_type {
+    ($fn_name:ident, $type:ty, $enum_type:path) => {
+        doc_comment! {
+            concat!("Consumes the enum, returning the `", stringify!($type),
+                    "`. Result is `Err` containing the original enum if contents aren't a `", stringify!($enum_type), "`."),
+            #[allow(clippy::result_large_err)]
+            pub fn $fn_name (self) -> Result<$type, CfgValue> {
+                if let $enum_type(x) = self {
+                    Ok(x)
+                } else { Err(self) }
+            }
+        }
+    };
+}
+
+macro_rules! as_list_type {
+    ($fn_name:ident, $type:ty, $enum_type:path, $convert:expr) => {
+        doc_comment! {
+            concat!("Returns the contents of a `List` as a `Vec<", stringify!($type),
+                    ">`. Result is `None` if contents aren't a `List`, or any of its elements isn't a `",
+                    stringify!($enum_type), "`."),
+            pub fn $fn_name (&self) -> Option<Vec<$type>> {
+                self.as_list()?.iter().map(|elem| {
+                    if let $enum_type(x) = elem {
+                        Some($convert(x))
+                    } else { None }
+                }).collect()
+            }
+        }
+    };
+}
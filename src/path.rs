@@ -0,0 +1,49 @@
+use std::fmt;
+
+/// A path into a `CfgMap`, split into its segments ahead of time.
+///
+/// Paths given to `get` as a `&str` are split on every call. When the same path is looked up
+/// repeatedly, such as within a hot loop, a `CfgPath` can be created once and passed to
+/// `get_path` or `get_path_mut` instead, which behave identically to `get` and `get_mut`.
+///
+/// ## Examples
+/// ```
+/// use cfgmap::{CfgMap, CfgPath, CfgValue::*, Checkable, Condition::*};
+///
+/// let mut cmap = CfgMap::new();
+/// cmap.add_auto("http/ports", List(vec![Int(80), Int(443)]));
+///
+/// let path = CfgPath::new("http/ports/1");
+///
+/// assert_eq!(path.segments(), &["http", "ports", "1"]);
+/// assert_eq!(path.to_string(), "http/ports/1");
+/// assert!(cmap.get_path(&path).check_that(IsExactlyInt(443)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CfgPath {
+    segments: Vec<String>,
+}
+
+impl CfgPath {
+    /// Parses a path of the form `"a/b/...y/z"` into its segments.
+    pub fn new(path: &str) -> CfgPath {
+        CfgPath { segments: path.split('/').map(String::from).collect() }
+    }
+
+    /// Returns the segments of the path, in order.
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+}
+
+impl From<&str> for CfgPath {
+    fn from(path: &str) -> Self {
+        CfgPath::new(path)
+    }
+}
+
+impl fmt::Display for CfgPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.segments.join("/"))
+    }
+}
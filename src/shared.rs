@@ -0,0 +1,98 @@
+use super::{CfgMap, CfgValue};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A `CfgMap` which can be shared across threads and reloaded, created using `CfgMap::share`.
+///
+/// This is a thin wrapper around an `Arc<RwLock<CfgMap>>`. Cloning it is cheap, and every clone
+/// refers to the same configuration, so a reload done through one clone is visible through all of them.
+/// `get_cloned` only holds the lock for as long as it takes to clone the value out, which keeps
+/// readers from blocking a reload for long.
+///
+/// Since a configuration is plain data, a panic while holding the lock doesn't make it unusable:
+/// the lock is recovered instead of propagating the poisoning.
+///
+/// ## Examples
+/// ```
+/// use cfgmap::{CfgMap, CfgValue::*};
+/// use std::thread;
+///
+/// let mut cmap = CfgMap::new();
+/// cmap.add("port", Int(8080));
+///
+/// let shared = cmap.share();
+/// let reader = shared.clone();
+///
+/// let port = thread::spawn(move || reader.get_cloned("port")).join().unwrap();
+/// assert_eq!(port, Some(Int(8080)));
+///
+/// let mut reloaded = CfgMap::new();
+/// reloaded.add("port", Int(9090));
+/// shared.reload(reloaded);
+///
+/// assert_eq!(shared.get_cloned("port"), Some(Int(9090)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SharedCfgMap(Arc<RwLock<CfgMap>>);
+
+impl SharedCfgMap {
+    /// Creates a new `SharedCfgMap` containing `map`.
+    pub fn new(map: CfgMap) -> SharedCfgMap {
+        SharedCfgMap(Arc::new(RwLock::new(map)))
+    }
+
+    /// Returns a clone of the value at `path`, or `None` if it doesn't exist. See `CfgMap::get`.
+    pub fn get_cloned(&self, path: &str) -> Option<CfgValue> {
+        self.read().get(path).cloned()
+    }
+
+    /// Replaces the configuration with `map`, returning the old one.
+    ///
+    /// Any callbacks registered using `on_change` on the old configuration are not carried over.
+    pub fn reload(&self, map: CfgMap) -> CfgMap {
+        std::mem::replace(&mut *self.write(), map)
+    }
+
+    /// Locks the configuration for reading, blocking until no writer holds the lock.
+    pub fn read(&self) -> RwLockReadGuard<'_, CfgMap> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the configuration for writing, blocking until no other reader or writer holds the lock.
+    pub fn write(&self) -> RwLockWriteGuard<'_, CfgMap> {
+        self.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl From<CfgMap> for SharedCfgMap {
+    fn from(map: CfgMap) -> Self {
+        SharedCfgMap::new(map)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SharedCfgMap;
+    use crate::{CfgMap, CfgValue::*};
+    use std::thread;
+
+    #[test]
+    fn is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SharedCfgMap>();
+    }
+
+    #[test]
+    fn recovers_from_poisoning() {
+        let shared = CfgMap::new().share();
+        let writer = shared.clone();
+
+        let result = thread::spawn(move || {
+            let mut cmap = writer.write();
+            cmap.add("port", Int(80)).unwrap();
+            panic!("poisoned");
+        }).join();
+
+        assert!(result.is_err());
+        assert_eq!(shared.get_cloned("port"), Some(Int(80)));
+    }
+}
@@ -0,0 +1,125 @@
+use super::{CfgMap, CfgValue, _Bool, _Float, _Int};
+
+#[cfg(feature = "from_toml")]
+use toml::value::Datetime;
+
+/// A visitor over the values within a `CfgMap`, used with `CfgValue::accept` and `CfgMap::accept`.
+///
+/// Each method is called as the corresponding value is reached, and does nothing by default, so only
+/// the relevant ones need to be implemented. Submaps and lists are visited recursively: a submap results
+/// in `visit_map_begin`, followed by `visit_key` and the value for each entry, and then `visit_map_end`.
+/// Lists work the same way, without the keys. Entries of a map are visited in sorted order by key.
+///
+/// This allows writing custom serializers, pretty-printers or validators without re-implementing the recursion.
+///
+/// ## Examples
+/// ```
+/// use cfgmap::{CfgMap, CfgVisitor, CfgValue::*};
+///
+/// /// Writes the configuration using a compact, json-like syntax.
+/// struct Printer(String);
+///
+/// impl CfgVisitor for Printer {
+///     fn visit_int(&mut self, value: i64) { self.0 += &value.to_string(); }
+///     fn visit_str(&mut self, value: &str) { self.0 += &format!("{:?}", value); }
+///     fn visit_map_begin(&mut self, _: usize) { self.0 += "{"; }
+///     fn visit_key(&mut self, key: &str) { self.0 += &format!("{}=", key); }
+///     fn visit_map_end(&mut self) { self.0 += "}"; }
+///     fn visit_list_begin(&mut self, _: usize) { self.0 += "["; }
+///     fn visit_list_end(&mut self) { self.0 += "]"; }
+/// }
+///
+/// let mut cmap = CfgMap::new();
+/// cmap.add_auto("http/port", Int(8080));
+/// cmap.add("hosts", List(vec![Str("a".into()), Str("b".into())]));
+///
+/// let mut printer = Printer(String::new());
+/// cmap.accept(&mut printer);
+///
+/// assert_eq!(printer.0, r#"{hosts=["a""b"]http={port=8080}}"#);
+/// ```
+pub trait CfgVisitor {
+    /// Called with the contents of an `Int`.
+    fn visit_int(&mut self, _value: _Int) {}
+
+    /// Called with the contents of a `Float`.
+    fn visit_float(&mut self, _value: _Float) {}
+
+    /// Called with the contents of a `Str`.
+    fn visit_str(&mut self, _value: &str) {}
+
+    /// Called with the contents of a `Bool`.
+    fn visit_bool(&mut self, _value: _Bool) {}
+
+    /// Called when a `Map` is reached, with its amount of entries.
+    fn visit_map_begin(&mut self, _len: usize) {}
+
+    /// Called with the key of each entry within a `Map`, before its value is visited.
+    fn visit_key(&mut self, _key: &str) {}
+
+    /// Called after every entry within a `Map` has been visited.
+    fn visit_map_end(&mut self) {}
+
+    /// Called when a `List` is reached, with its amount of elements.
+    fn visit_list_begin(&mut self, _len: usize) {}
+
+    /// Called after every element within a `List` has been visited.
+    fn visit_list_end(&mut self) {}
+
+    /// Called with the contents of a `Datetime`. Only available if using `from_toml`.
+    #[cfg(feature = "from_toml")]
+    fn visit_datetime(&mut self, _value: &Datetime) {}
+
+    /// Called when a `Null` is reached. Only available if using `from_json` or `from_yaml`.
+    #[cfg(any(feature = "from_json", feature = "from_yaml"))]
+    fn visit_null(&mut self) {}
+
+    /// Called when a `BadValue` is reached. Only available if using `from_yaml`.
+    #[cfg(feature = "from_yaml")]
+    fn visit_bad_value(&mut self) {}
+
+    /// Called with the contents of an `Alias`. Only available if using `from_yaml`.
+    #[cfg(feature = "from_yaml")]
+    fn visit_alias(&mut self, _value: usize) {}
+}
+
+pub(crate) fn visit_map<V: CfgVisitor + ?Sized>(map: &CfgMap, visitor: &mut V) {
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+
+    visitor.visit_map_begin(keys.len());
+
+    for key in keys {
+        visitor.visit_key(key);
+        visit_value(&map.internal_map[key], visitor);
+    }
+
+    visitor.visit_map_end();
+}
+
+pub(crate) fn visit_value<V: CfgVisitor + ?Sized>(value: &CfgValue, visitor: &mut V) {
+    match value {
+        CfgValue::Int(x) => visitor.visit_int(*x),
+        CfgValue::Float(x) => visitor.visit_float(*x),
+        CfgValue::Str(x) => visitor.visit_str(x),
+        CfgValue::Bool(x) => visitor.visit_bool(*x),
+        CfgValue::Map(x) => visit_map(x, visitor),
+        CfgValue::List(x) => {
+            visitor.visit_list_begin(x.len());
+            x.iter().for_each(|elem| visit_value(elem, visitor));
+            visitor.visit_list_end();
+        },
+
+        #[cfg(feature = "from_toml")]
+        CfgValue::Datetime(x) => visitor.visit_datetime(x),
+
+        #[cfg(any(feature = "from_json", feature = "from_yaml"))]
+        CfgValue::Null => visitor.visit_null(),
+
+        #[cfg(feature = "from_yaml")]
+        CfgValue::BadValue => visitor.visit_bad_value(),
+
+        #[cfg(feature = "from_yaml")]
+        CfgValue::Alias(x) => visitor.visit_alias(*x),
+    }
+}